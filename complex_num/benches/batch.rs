@@ -0,0 +1,102 @@
+// Batch functions against one operator call per element
+//   cargo bench -p complex_num --bench batch
+//   cargo +nightly bench -p complex_num --bench batch --features simd
+
+use complex_num::batch::{add_slices, conj_in_place, dot, mul_slices, norm_sqr_slice};
+use complex_num::Complex;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+const SIZES: [usize; 2] = [1024, 65536];
+
+fn samples(n: usize) -> Vec<Complex<f32>> {
+    (0..n)
+        .map(|k| Complex::new((k as f32 * 0.1).sin(), (k as f32 * 0.3).cos()))
+        .collect()
+}
+
+fn bench_mul(c: &mut Criterion) {
+    let mut group = c.benchmark_group("mul");
+    for n in SIZES {
+        let (a, b) = (samples(n), samples(n));
+        let mut out = vec![Complex::default(); n];
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(BenchmarkId::new("operator", n), &n, |bench, _| {
+            bench.iter(|| {
+                for ((o, x), y) in out.iter_mut().zip(black_box(&a)).zip(black_box(&b)) {
+                    *o = x * y;
+                }
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("batch", n), &n, |bench, _| {
+            bench.iter(|| mul_slices(black_box(&a), black_box(&b), &mut out))
+        });
+    }
+    group.finish();
+}
+
+fn bench_add(c: &mut Criterion) {
+    let mut group = c.benchmark_group("add");
+    for n in SIZES {
+        let (a, b) = (samples(n), samples(n));
+        let mut out = vec![Complex::default(); n];
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(BenchmarkId::new("operator", n), &n, |bench, _| {
+            bench.iter(|| {
+                for ((o, x), y) in out.iter_mut().zip(black_box(&a)).zip(black_box(&b)) {
+                    *o = x + y;
+                }
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("batch", n), &n, |bench, _| {
+            bench.iter(|| add_slices(black_box(&a), black_box(&b), &mut out))
+        });
+    }
+    group.finish();
+}
+
+fn bench_conj_norm(c: &mut Criterion) {
+    let mut group = c.benchmark_group("conj_norm_sqr");
+    let n = SIZES[1];
+    let mut a = samples(n);
+    let mut norms = vec![0.0; n];
+    group.throughput(Throughput::Elements(n as u64));
+    group.bench_function("operator", |bench| {
+        bench.iter(|| {
+            for (z, o) in black_box(&mut a).iter_mut().zip(norms.iter_mut()) {
+                *z = z.conjugate();
+                *o = z.norm_sqr();
+            }
+        })
+    });
+    group.bench_function("batch", |bench| {
+        bench.iter(|| {
+            conj_in_place(black_box(&mut a));
+            norm_sqr_slice(&a, &mut norms);
+        })
+    });
+    group.finish();
+}
+
+fn bench_dot(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dot");
+    for n in SIZES {
+        let (a, b) = (samples(n), samples(n));
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(BenchmarkId::new("operator", n), &n, |bench, _| {
+            bench.iter(|| {
+                black_box(&a)
+                    .iter()
+                    .zip(black_box(&b))
+                    .map(|(x, y)| x * y)
+                    .sum::<Complex<f32>>()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("batch", n), &n, |bench, _| {
+            bench.iter(|| dot(black_box(&a), black_box(&b)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_mul, bench_add, bench_conj_norm, bench_dot);
+criterion_main!(benches);
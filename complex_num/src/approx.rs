@@ -0,0 +1,206 @@
+// Approximate equality for floating point Complex numbers
+// results of floating point math are rounded, so == is almost never
+// the right comparison for computed values
+// - approx_eq: the distance |a - b| is within an absolute tolerance
+//   or within a relative tolerance of the larger modulus
+// - ulps_diff and ulps_eq: how many representable floats lie between
+//   the parts, for comparisons at the precision of the type
+// - assert_complex_eq! macro for tests, prints both numbers and their
+//   difference when the check fails
+//     assert_complex_eq!(a, b);                              // 1e-9, 1e-9
+//     assert_complex_eq!(a, b, abs_tol = 1e-12, rel_tol = 0.0);
+//     assert_complex_eq!(a, b, ulps = 4);
+
+use crate::{Complex, Float};
+
+impl<T: Float> Complex<T> {
+    // |self - other| <= max(abs_tol, rel_tol * max(|self|, |other|))
+    // the absolute tolerance matters near 0 where a relative one is useless
+    // infinite numbers are only equal to themselves, NaN to nothing
+    pub fn approx_eq(&self, other: &Self, abs_tol: T, rel_tol: T) -> bool {
+        if !self.is_finite() || !other.is_finite() {
+            return self == other;
+        }
+        let diff = (*self - *other).norm();
+        let largest = if self.norm() > other.norm() {
+            self.norm()
+        } else {
+            other.norm()
+        };
+        let rel = rel_tol * largest;
+        diff <= abs_tol || diff <= rel
+    }
+
+    // largest distance of the two parts in units in the last place
+    // None if any part is NaN
+    pub fn ulps_diff(&self, other: &Self) -> Option<u64> {
+        let real = self.real.ulps_between(other.real)?;
+        let imag = self.imag.ulps_between(other.imag)?;
+        Some(real.max(imag))
+    }
+
+    // both parts are at most max_ulps representable values apart
+    pub fn ulps_eq(&self, other: &Self, max_ulps: u64) -> bool {
+        self.ulps_diff(other).is_some_and(|d| d <= max_ulps)
+    }
+
+    fn is_finite(&self) -> bool {
+        self.real.is_finite() && self.imag.is_finite()
+    }
+}
+
+// Assert that two floating point Complex numbers are approximately equal
+// with the default tolerances (1e-9 absolute and relative), explicit
+// tolerances or a maximum distance in ULPs, see the top of approx.rs
+#[macro_export]
+macro_rules! assert_complex_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::assert_complex_eq!($left, $right, abs_tol = 1e-9, rel_tol = 1e-9)
+    };
+    ($left:expr, $right:expr, abs_tol = $abs:expr, rel_tol = $rel:expr $(,)?) => {
+        $crate::approx::assert_approx_eq(&$left, &$right, $abs, $rel)
+    };
+    ($left:expr, $right:expr, ulps = $ulps:expr $(,)?) => {
+        $crate::approx::assert_ulps_eq(&$left, &$right, $ulps)
+    };
+}
+
+// the macro expands to these so that the failure message is built
+// in one place, track_caller reports the line of the macro call
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_approx_eq<T: Float>(left: &Complex<T>, right: &Complex<T>, abs_tol: T, rel_tol: T) {
+    if !left.approx_eq(right, abs_tol, rel_tol) {
+        panic!(
+            "assertion `left ≈ right` failed\n  left: {}\n right: {}\n  diff: {} (|diff| = {})\n   tol: abs {}, rel {}",
+            left,
+            right,
+            *left - *right,
+            (*left - *right).norm(),
+            abs_tol,
+            rel_tol
+        );
+    }
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_ulps_eq<T: Float>(left: &Complex<T>, right: &Complex<T>, max_ulps: u64) {
+    if !left.ulps_eq(right, max_ulps) {
+        let ulps = match left.ulps_diff(right) {
+            Some(d) => d.to_string(),
+            None => "NaN".to_string(),
+        };
+        panic!(
+            "assertion `left ≈ right` failed\n  left: {}\n right: {}\n  diff: {} ({} ulps)\n   tol: {} ulps",
+            left,
+            right,
+            *left - *right,
+            ulps,
+            max_ulps
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::panic;
+
+    // absolute tolerance near 0, relative tolerance for large numbers
+    #[test]
+    fn test_approx_eq() {
+        let a = Complex::new(1e-12, 0.0);
+        let b = Complex::new(0.0, -1e-12);
+        assert!(a.approx_eq(&b, 1e-11, 0.0));
+        assert!(!a.approx_eq(&b, 0.0, 0.5));
+        let a = Complex::new(1e10, 1e10);
+        let b = Complex::new(1e10 + 1.0, 1e10);
+        assert!(a.approx_eq(&b, 0.0, 1e-9));
+        assert!(!a.approx_eq(&b, 0.5, 1e-12));
+    }
+
+    // infinite parts must match exactly, NaN never matches
+    #[test]
+    fn test_approx_eq_special() {
+        let inf = Complex::new(f64::INFINITY, 0.0);
+        assert!(inf.approx_eq(&inf, 1.0, 1.0));
+        assert!(!inf.approx_eq(&Complex::new(f64::MAX, 0.0), 1.0, 1.0));
+        let nan = Complex::new(f64::NAN, 0.0);
+        assert!(!nan.approx_eq(&nan, 1.0, 1.0));
+        assert_eq!(None, nan.ulps_diff(&nan));
+    }
+
+    // neighbouring floats are 1 ulp apart, across 0 as well
+    #[test]
+    fn test_ulps() {
+        let one = Complex::new(1.0f64, -1.0);
+        let next = Complex::new(1.0f64.next_up(), -1.0);
+        assert_eq!(Some(1), one.ulps_diff(&next));
+        assert_eq!(
+            Some(0),
+            Complex::new(0.0, 0.0).ulps_diff(&Complex::new(-0.0, 0.0))
+        );
+        let tiny = f32::from_bits(1);
+        assert_eq!(
+            Some(2),
+            Complex::new(tiny, 0.0).ulps_diff(&Complex::new(-tiny, 0.0))
+        );
+        // 0.1 + 0.2 is the float right after 0.3
+        assert!(Complex::new(0.1 + 0.2, 0.0).ulps_eq(&Complex::new(0.3, 0.0), 1));
+        assert!(!Complex::new(0.1 + 0.2, 0.0).ulps_eq(&Complex::new(0.3, 0.0), 0));
+    }
+
+    // the default ulps_between of Float is within a factor of 2
+    // of the exact count
+    #[test]
+    fn test_estimate_ulps() {
+        use crate::num::estimate_ulps;
+        for (a, b) in [
+            (1.0, 1.0 + 1e-12),
+            (-3.0, -3.5),
+            (1e300, 1.1e300),
+            (0.1 + 0.2, 0.3),
+        ] {
+            let exact = a.ulps_between(b).unwrap();
+            let estimate = estimate_ulps(a, b).unwrap();
+            assert!(exact / 2 <= estimate && estimate <= 2 * exact, "{a} {b}");
+        }
+        assert_eq!(Some(0), estimate_ulps(0.0, -0.0));
+        assert_eq!(Some(u64::MAX), estimate_ulps(f64::INFINITY, 1.0));
+        assert_eq!(None, estimate_ulps(f32::NAN, 1.0));
+    }
+
+    #[test]
+    fn test_assert_macro() {
+        let z = Complex::new(0.1, 0.2) * Complex::new(3.0, 0.0);
+        assert_complex_eq!(Complex::new(0.3, 0.6), z);
+        assert_complex_eq!(Complex::new(0.3, 0.6), z, abs_tol = 1e-15, rel_tol = 0.0);
+        assert_complex_eq!(Complex::new(0.3, 0.6), z, ulps = 2);
+        assert_complex_eq!(Complex::new(1.0f32, 0.0), Complex::new(1.0f32, 1e-10));
+    }
+
+    // the failure message shows both numbers and how far apart they are
+    #[test]
+    fn test_assert_message() {
+        let result = panic::catch_unwind(|| {
+            assert_complex_eq!(Complex::new(1.0, 2.0), Complex::new(1.0, 2.5));
+        });
+        let err = result.unwrap_err();
+        let msg = err.downcast_ref::<String>().unwrap();
+        assert_eq!(
+            "assertion `left ≈ right` failed\n  left: 1 + 2i\n right: 1 + 2.5i\n  diff: -0.5i (|diff| = 0.5)\n   tol: abs 0.000000001, rel 0.000000001",
+            msg
+        );
+        let result = panic::catch_unwind(|| {
+            assert_complex_eq!(
+                Complex::new(1.0, 0.0),
+                Complex::new(1.0f64.next_up(), 0.0),
+                ulps = 0
+            );
+        });
+        let err = result.unwrap_err();
+        let msg = err.downcast_ref::<String>().unwrap();
+        assert!(msg.ends_with("(1 ulps)\n   tol: 0 ulps"), "{msg}");
+    }
+}
@@ -0,0 +1,340 @@
+// Batch operations on slices of Complex<f32> and Complex<f64>
+// - add_slices and mul_slices, out[k] = a[k] op b[k]
+// - conj_in_place
+// - norm_sqr_slice, out[k] = |a[k]|^2
+// - dot (sum of a[k] b[k]) and dot_conj (sum of conj(a[k]) b[k])
+// with the simd feature the kernels use portable SIMD (std::simd) on the
+// interleaved re, im view of the slices (see cast.rs), splitting it into
+// real and imaginary lanes where the parts mix; std::simd is nightly only
+// so without the feature the same functions run plain scalar loops,
+// which is also what handles the elements after the last full vector
+// reductions keep one partial sum per lane, so dot can differ from a
+// sequential sum in the last bits
+// Note: all functions panic if the slices have different lengths
+
+use crate::{Complex, Float};
+
+pub fn add_slices<T: Batch>(a: &[Complex<T>], b: &[Complex<T>], out: &mut [Complex<T>]) {
+    check_len(a.len(), b.len(), out.len());
+    T::add_slices(a, b, out);
+}
+
+pub fn mul_slices<T: Batch>(a: &[Complex<T>], b: &[Complex<T>], out: &mut [Complex<T>]) {
+    check_len(a.len(), b.len(), out.len());
+    T::mul_slices(a, b, out);
+}
+
+pub fn conj_in_place<T: Batch>(a: &mut [Complex<T>]) {
+    T::conj_in_place(a);
+}
+
+pub fn norm_sqr_slice<T: Batch>(a: &[Complex<T>], out: &mut [T]) {
+    check_len(a.len(), a.len(), out.len());
+    T::norm_sqr_slice(a, out);
+}
+
+pub fn dot<T: Batch>(a: &[Complex<T>], b: &[Complex<T>]) -> Complex<T> {
+    check_len(a.len(), b.len(), b.len());
+    T::dot(a, b, false)
+}
+
+// the inner product of complex vectors
+pub fn dot_conj<T: Batch>(a: &[Complex<T>], b: &[Complex<T>]) -> Complex<T> {
+    check_len(a.len(), b.len(), b.len());
+    T::dot(a, b, true)
+}
+
+fn check_len(a: usize, b: usize, out: usize) {
+    assert!(a == b && b == out, "slices have different lengths");
+}
+
+// scalars with batch kernels, implemented for f32 and f64
+// the slices passed in already have matching lengths
+pub trait Batch: Float {
+    fn add_slices(a: &[Complex<Self>], b: &[Complex<Self>], out: &mut [Complex<Self>]);
+    fn mul_slices(a: &[Complex<Self>], b: &[Complex<Self>], out: &mut [Complex<Self>]);
+    fn conj_in_place(a: &mut [Complex<Self>]);
+    fn norm_sqr_slice(a: &[Complex<Self>], out: &mut [Self]);
+    fn dot(a: &[Complex<Self>], b: &[Complex<Self>], conj: bool) -> Complex<Self>;
+}
+
+// each method runs the SIMD kernel over the whole vectors, which returns
+// how many elements it did, and the scalar code over the rest
+macro_rules! impl_batch {
+    ($($t:ty, $simd:ident;)*) => {
+        $(
+            impl Batch for $t {
+                fn add_slices(a: &[Complex<$t>], b: &[Complex<$t>], out: &mut [Complex<$t>]) {
+                    #[cfg(feature = "simd")]
+                    let n = $simd::add_slices(a, b, out);
+                    #[cfg(not(feature = "simd"))]
+                    let n = 0;
+                    scalar::add_slices(&a[n..], &b[n..], &mut out[n..]);
+                }
+
+                fn mul_slices(a: &[Complex<$t>], b: &[Complex<$t>], out: &mut [Complex<$t>]) {
+                    #[cfg(feature = "simd")]
+                    let n = $simd::mul_slices(a, b, out);
+                    #[cfg(not(feature = "simd"))]
+                    let n = 0;
+                    scalar::mul_slices(&a[n..], &b[n..], &mut out[n..]);
+                }
+
+                fn conj_in_place(a: &mut [Complex<$t>]) {
+                    #[cfg(feature = "simd")]
+                    let n = $simd::conj_in_place(a);
+                    #[cfg(not(feature = "simd"))]
+                    let n = 0;
+                    scalar::conj_in_place(&mut a[n..]);
+                }
+
+                fn norm_sqr_slice(a: &[Complex<$t>], out: &mut [$t]) {
+                    #[cfg(feature = "simd")]
+                    let n = $simd::norm_sqr_slice(a, out);
+                    #[cfg(not(feature = "simd"))]
+                    let n = 0;
+                    scalar::norm_sqr_slice(&a[n..], &mut out[n..]);
+                }
+
+                fn dot(a: &[Complex<$t>], b: &[Complex<$t>], conj: bool) -> Complex<$t> {
+                    #[cfg(feature = "simd")]
+                    let (n, sum) = $simd::dot(a, b, conj);
+                    #[cfg(not(feature = "simd"))]
+                    let (n, sum) = (0, Complex::new(0.0, 0.0));
+                    sum + scalar::dot(&a[n..], &b[n..], conj)
+                }
+            }
+        )*
+    };
+}
+
+impl_batch! {
+    f32, simd_f32;
+    f64, simd_f64;
+}
+
+mod scalar {
+    use crate::{Complex, Float};
+
+    pub fn add_slices<T: Float>(a: &[Complex<T>], b: &[Complex<T>], out: &mut [Complex<T>]) {
+        for ((o, x), y) in out.iter_mut().zip(a).zip(b) {
+            *o = x + y;
+        }
+    }
+
+    pub fn mul_slices<T: Float>(a: &[Complex<T>], b: &[Complex<T>], out: &mut [Complex<T>]) {
+        for ((o, x), y) in out.iter_mut().zip(a).zip(b) {
+            *o = x * y;
+        }
+    }
+
+    pub fn conj_in_place<T: Float>(a: &mut [Complex<T>]) {
+        for z in a {
+            z.imag = -z.imag;
+        }
+    }
+
+    pub fn norm_sqr_slice<T: Float>(a: &[Complex<T>], out: &mut [T]) {
+        for (o, z) in out.iter_mut().zip(a) {
+            *o = z.norm_sqr();
+        }
+    }
+
+    pub fn dot<T: Float>(a: &[Complex<T>], b: &[Complex<T>], conj: bool) -> Complex<T> {
+        a.iter()
+            .zip(b)
+            .map(|(x, y)| if conj { x.conjugate() * y } else { x * y })
+            .sum()
+    }
+}
+
+// the SIMD kernels only differ in the scalar type and the number of lanes,
+// LANES scalars of either type fill one 256 bit AVX register
+#[cfg(feature = "simd")]
+macro_rules! simd_kernels {
+    ($($name:ident, $t:ty, $lanes:literal;)*) => {
+        $(
+            mod $name {
+                use crate::Complex;
+                use std::simd::num::SimdFloat;
+                use std::simd::Simd;
+
+                const LANES: usize = $lanes;
+                type V = Simd<$t, LANES>;
+
+                // addition does not mix the parts, so it works on the
+                // interleaved scalars directly
+                pub fn add_slices(a: &[Complex<$t>], b: &[Complex<$t>], out: &mut [Complex<$t>]) -> usize {
+                    let (x, y) = (Complex::as_interleaved(a), Complex::as_interleaved(b));
+                    let o = Complex::as_interleaved_mut(out);
+                    let n = x.len() - x.len() % LANES;
+                    for k in (0..n).step_by(LANES) {
+                        (V::from_slice(&x[k..]) + V::from_slice(&y[k..])).copy_to_slice(&mut o[k..]);
+                    }
+                    n / 2
+                }
+
+                // two vectors of interleaved scalars give one vector of
+                // real parts and one of imaginary parts
+                fn load(s: &[$t], k: usize) -> (V, V) {
+                    V::from_slice(&s[k..]).deinterleave(V::from_slice(&s[k + LANES..]))
+                }
+
+                pub fn mul_slices(a: &[Complex<$t>], b: &[Complex<$t>], out: &mut [Complex<$t>]) -> usize {
+                    let (x, y) = (Complex::as_interleaved(a), Complex::as_interleaved(b));
+                    let o = Complex::as_interleaved_mut(out);
+                    let n = x.len() - x.len() % (2 * LANES);
+                    for k in (0..n).step_by(2 * LANES) {
+                        let ((xr, xi), (yr, yi)) = (load(x, k), load(y, k));
+                        let (lo, hi) = (xr * yr - xi * yi).interleave(xr * yi + xi * yr);
+                        lo.copy_to_slice(&mut o[k..]);
+                        hi.copy_to_slice(&mut o[k + LANES..]);
+                    }
+                    n / 2
+                }
+
+                pub fn conj_in_place(a: &mut [Complex<$t>]) -> usize {
+                    let x = Complex::as_interleaved_mut(a);
+                    let sign = V::from_array(std::array::from_fn(|k| if k % 2 == 0 { 1.0 } else { -1.0 }));
+                    let n = x.len() - x.len() % LANES;
+                    for k in (0..n).step_by(LANES) {
+                        (V::from_slice(&x[k..]) * sign).copy_to_slice(&mut x[k..]);
+                    }
+                    n / 2
+                }
+
+                pub fn norm_sqr_slice(a: &[Complex<$t>], out: &mut [$t]) -> usize {
+                    let x = Complex::as_interleaved(a);
+                    let n = x.len() - x.len() % (2 * LANES);
+                    for k in (0..n).step_by(2 * LANES) {
+                        let (re, im) = load(x, k);
+                        (re * re + im * im).copy_to_slice(&mut out[k / 2..]);
+                    }
+                    n / 2
+                }
+
+                pub fn dot(a: &[Complex<$t>], b: &[Complex<$t>], conj: bool) -> (usize, Complex<$t>) {
+                    let (x, y) = (Complex::as_interleaved(a), Complex::as_interleaved(b));
+                    let n = x.len() - x.len() % (2 * LANES);
+                    // conjugating a only flips the sign of its imaginary lanes
+                    let sign = V::splat(if conj { -1.0 } else { 1.0 });
+                    let (mut re, mut im) = (V::splat(0.0), V::splat(0.0));
+                    for k in (0..n).step_by(2 * LANES) {
+                        let ((xr, xi), (yr, yi)) = (load(x, k), load(y, k));
+                        let xi = xi * sign;
+                        re += xr * yr - xi * yi;
+                        im += xr * yi + xi * yr;
+                    }
+                    (n / 2, Complex::new(re.reduce_sum(), im.reduce_sum()))
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "simd")]
+simd_kernels! {
+    simd_f32, f32, 8;
+    simd_f64, f64, 4;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_complex_eq;
+
+    // lengths around the vector sizes exercise both the kernels and the tail
+    const LENGTHS: [usize; 8] = [0, 1, 3, 4, 7, 8, 9, 37];
+
+    fn samples(n: usize, seed: f64) -> Vec<Complex<f64>> {
+        (0..n)
+            .map(|k| {
+                let t = k as f64 + seed;
+                Complex::new((t * 0.7).sin() * 3.0, (t * 1.3).cos() - 0.5)
+            })
+            .collect()
+    }
+
+    // same results as the operators one element at a time
+    #[test]
+    fn test_add_mul() {
+        for n in LENGTHS {
+            let (a, b) = (samples(n, 0.0), samples(n, 5.0));
+            let mut out = vec![Complex::default(); n];
+            add_slices(&a, &b, &mut out);
+            for k in 0..n {
+                assert_eq!(a[k] + b[k], out[k]);
+            }
+            mul_slices(&a, &b, &mut out);
+            for k in 0..n {
+                assert_complex_eq!(a[k] * b[k], out[k], abs_tol = 1e-9, rel_tol = 0.0);
+            }
+        }
+    }
+
+    // conjugate and squared norm
+    #[test]
+    fn test_conj_norm_sqr() {
+        for n in LENGTHS {
+            let a = samples(n, 1.0);
+            let mut c = a.clone();
+            conj_in_place(&mut c);
+            for k in 0..n {
+                assert_eq!(a[k].conjugate(), c[k]);
+            }
+            let mut out = vec![0.0; n];
+            norm_sqr_slice(&a, &mut out);
+            for k in 0..n {
+                assert!((a[k].norm_sqr() - out[k]).abs() < 1e-12);
+            }
+        }
+    }
+
+    // dot products agree with a sequential sum
+    #[test]
+    fn test_dot() {
+        for n in LENGTHS {
+            let (a, b) = (samples(n, 2.0), samples(n, 3.0));
+            let expected: Complex<f64> = a.iter().zip(&b).map(|(x, y)| x * y).sum();
+            assert_complex_eq!(expected, dot(&a, &b), abs_tol = 1e-9, rel_tol = 0.0);
+            let expected: Complex<f64> = a.iter().zip(&b).map(|(x, y)| x.conjugate() * y).sum();
+            assert_complex_eq!(expected, dot_conj(&a, &b), abs_tol = 1e-9, rel_tol = 0.0);
+        }
+        // <a, a> is the squared norm of the vector
+        let a = samples(20, 0.0);
+        let n: f64 = a.iter().map(|z| z.norm_sqr()).sum();
+        assert_complex_eq!(
+            Complex::new(n, 0.0),
+            dot_conj(&a, &a),
+            abs_tol = 1e-9,
+            rel_tol = 0.0
+        );
+    }
+
+    // f32 has its own kernels with more lanes
+    #[test]
+    fn test_f32() {
+        let a: Vec<Complex<f32>> = (0..19).map(|k| Complex::new(k as f32, 1.0)).collect();
+        let mut out = vec![Complex::default(); 19];
+        mul_slices(&a, &a, &mut out);
+        assert_eq!(Complex::new(143.0, 24.0), out[12]);
+        assert_eq!(Complex::new(323.0, 36.0), out[18]);
+        let mut norms = vec![0.0; 19];
+        norm_sqr_slice(&a, &mut norms);
+        assert_eq!(26.0, norms[5]);
+        // sum of k^2 - 1 and of 2k for k < 16
+        let ones = vec![Complex::new(1.0f32, 0.0); 16];
+        assert_eq!(Complex::new(1224.0, 240.0), dot(&out[..16], &ones));
+        let mut c = a.clone();
+        conj_in_place(&mut c);
+        assert_eq!(Complex::new(12.0, -1.0), c[12]);
+    }
+
+    // mismatched lengths are a bug in the caller
+    #[test]
+    #[should_panic]
+    fn test_length_mismatch() {
+        let mut out = vec![Complex::default(); 3];
+        add_slices(&samples(3, 0.0), &samples(4, 0.0), &mut out);
+    }
+}
@@ -0,0 +1,565 @@
+// Arbitrary precision signed integer
+// used as the scalar of Complex<BigInt> for Gaussian integer work
+// that would overflow the primitive integers, and as the numerator
+// and denominator of Rational
+// the number is stored as sign and magnitude, the magnitude is a
+// list of base 2^32 digits with the least significant digit first
+// and no leading zero digits, so 0 is the empty list
+// the operators behave like the ones on the primitive signed integers
+// (division truncates towards zero, the remainder has the sign of the
+// dividend) but never overflow
+
+use crate::{Num, ParseNumberError, SignedInt};
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigInt {
+    negative: bool,
+    mag: Vec<u32>,
+}
+
+// largest power of 10 that fits into a digit
+// Display and FromStr work on chunks of 9 decimal digits
+const DECIMAL_CHUNK: u32 = 1_000_000_000;
+const DECIMAL_CHUNK_DIGITS: usize = 9;
+
+impl BigInt {
+    pub fn is_zero(&self) -> bool {
+        self.mag.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn abs(&self) -> Self {
+        Self::from_parts(false, self.mag.clone())
+    }
+
+    // greatest common divisor using the Euclidean algorithm
+    // the result is never negative, gcd(0, 0) is 0
+    pub fn gcd(&self, other: &Self) -> Self {
+        let mut a = self.abs();
+        let mut b = other.abs();
+        while !b.is_zero() {
+            let (_, r) = div_rem_mag(&a.mag, &b.mag);
+            a = b;
+            b = Self::from_parts(false, r);
+        }
+        a
+    }
+
+    // self^exp by repeated squaring
+    pub fn pow(&self, mut exp: u32) -> Self {
+        let mut base = self.clone();
+        let mut result = Self::from(1);
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result * base.clone();
+            }
+            base = base.clone() * base;
+            exp >>= 1;
+        }
+        result
+    }
+
+    // quotient truncated towards zero and remainder with the sign of self
+    // Note: panics when dividing by zero like the primitive integers
+    pub fn div_rem(&self, rhs: &Self) -> (Self, Self) {
+        assert!(!rhs.is_zero(), "attempt to divide by zero");
+        let (q, r) = div_rem_mag(&self.mag, &rhs.mag);
+        (
+            Self::from_parts(self.negative != rhs.negative, q),
+            Self::from_parts(self.negative, r),
+        )
+    }
+
+    // drop leading zero digits and make sure 0 is never negative
+    fn from_parts(negative: bool, mut mag: Vec<u32>) -> Self {
+        trim(&mut mag);
+        let negative = negative && !mag.is_empty();
+        Self { negative, mag }
+    }
+
+    fn from_u128(negative: bool, mut m: u128) -> Self {
+        let mut mag = vec![];
+        while m > 0 {
+            mag.push(m as u32);
+            m >>= 32;
+        }
+        Self::from_parts(negative, mag)
+    }
+}
+
+// conversions from all the primitive integers
+macro_rules! impl_from {
+    (signed: $($s:ty),*; unsigned: $($u:ty),*) => {
+        $(
+            impl From<$s> for BigInt {
+                fn from(x: $s) -> Self {
+                    Self::from_u128(x < 0, (x as i128).unsigned_abs())
+                }
+            }
+        )*
+        $(
+            impl From<$u> for BigInt {
+                fn from(x: $u) -> Self {
+                    Self::from_u128(false, x as u128)
+                }
+            }
+        )*
+    };
+}
+
+impl_from!(signed: i8, i16, i32, i64, i128, isize; unsigned: u8, u16, u32, u64, u128, usize);
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_mag(&self.mag, &other.mag),
+            (true, true) => cmp_mag(&other.mag, &self.mag),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add for BigInt {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        if self.negative == rhs.negative {
+            return Self::from_parts(self.negative, add_mag(&self.mag, &rhs.mag));
+        }
+        // different signs, subtract the smaller magnitude from the larger
+        match cmp_mag(&self.mag, &rhs.mag) {
+            Ordering::Less => Self::from_parts(rhs.negative, sub_mag(&rhs.mag, &self.mag)),
+            _ => Self::from_parts(self.negative, sub_mag(&self.mag, &rhs.mag)),
+        }
+    }
+}
+
+impl Sub for BigInt {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl Mul for BigInt {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self::from_parts(self.negative != rhs.negative, mul_mag(&self.mag, &rhs.mag))
+    }
+}
+
+impl Div for BigInt {
+    type Output = Self;
+    fn div(self, rhs: Self) -> Self::Output {
+        self.div_rem(&rhs).0
+    }
+}
+
+impl Rem for BigInt {
+    type Output = Self;
+    fn rem(self, rhs: Self) -> Self::Output {
+        self.div_rem(&rhs).1
+    }
+}
+
+impl Neg for BigInt {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Self::from_parts(!self.negative, self.mag)
+    }
+}
+
+impl Num for BigInt {
+    fn zero() -> Self {
+        Self::default()
+    }
+    fn one() -> Self {
+        Self::from(1)
+    }
+    // rounds to the nearest f64, infinite when out of range
+    fn to_f64(self) -> f64 {
+        let m = self
+            .mag
+            .iter()
+            .rev()
+            .fold(0.0, |acc, &d| acc * 4294967296.0 + d as f64);
+        if self.negative {
+            -m
+        } else {
+            m
+        }
+    }
+    fn trunc(self) -> Self {
+        self
+    }
+}
+
+impl SignedInt for BigInt {}
+
+// decimal digits with a leading - for negative numbers
+// width, fill, alignment and the sign flag work like for i64
+impl Display for BigInt {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        // peel off 9 decimal digits at a time, least significant first
+        let mut chunks = vec![];
+        let mut mag = self.mag.clone();
+        while !mag.is_empty() {
+            chunks.push(div_small(&mut mag, DECIMAL_CHUNK));
+        }
+        let mut s = chunks.pop().unwrap_or(0).to_string();
+        for c in chunks.iter().rev() {
+            s.push_str(&format!("{:0width$}", c, width = DECIMAL_CHUNK_DIGITS));
+        }
+        f.pad_integral(!self.negative, "", &s)
+    }
+}
+
+// optional + or - sign followed by decimal digits
+impl FromStr for BigInt {
+    type Err = ParseNumberError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        if digits.is_empty() {
+            return Err(ParseNumberError::Empty);
+        }
+        if !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ParseNumberError::InvalidDigit);
+        }
+        // the first chunk is the short one so the rest are all full
+        let mut mag = vec![];
+        let first = match digits.len() % DECIMAL_CHUNK_DIGITS {
+            0 => DECIMAL_CHUNK_DIGITS,
+            n => n,
+        };
+        let mut start = 0;
+        let mut end = first;
+        while start < digits.len() {
+            let chunk = &digits[start..end];
+            let scale = 10u32.pow(chunk.len() as u32);
+            // only ascii digits, cannot fail or overflow
+            let value = chunk.parse::<u32>().unwrap();
+            mul_add_small(&mut mag, scale, value);
+            start = end;
+            end += DECIMAL_CHUNK_DIGITS;
+        }
+        Ok(Self::from_parts(negative, mag))
+    }
+}
+
+fn trim(mag: &mut Vec<u32>) {
+    while mag.last() == Some(&0) {
+        mag.pop();
+    }
+}
+
+fn cmp_mag(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut sum = Vec::with_capacity(long.len() + 1);
+    let mut carry = 0u64;
+    for (i, &d) in long.iter().enumerate() {
+        let s = d as u64 + short.get(i).copied().unwrap_or(0) as u64 + carry;
+        sum.push(s as u32);
+        carry = s >> 32;
+    }
+    sum.push(carry as u32);
+    trim(&mut sum);
+    sum
+}
+
+// a - b for a >= b
+fn sub_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut diff = Vec::with_capacity(a.len());
+    let mut borrow = 0i64;
+    for (i, &d) in a.iter().enumerate() {
+        let t = d as i64 - b.get(i).copied().unwrap_or(0) as i64 - borrow;
+        diff.push(t as u32);
+        borrow = (t < 0) as i64;
+    }
+    trim(&mut diff);
+    diff
+}
+
+// schoolbook multiplication
+fn mul_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    if a.is_empty() || b.is_empty() {
+        return vec![];
+    }
+    let mut prod = vec![0u32; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, &y) in b.iter().enumerate() {
+            let t = x as u64 * y as u64 + prod[i + j] as u64 + carry;
+            prod[i + j] = t as u32;
+            carry = t >> 32;
+        }
+        prod[i + b.len()] = carry as u32;
+    }
+    trim(&mut prod);
+    prod
+}
+
+// mag = mag * m + a
+fn mul_add_small(mag: &mut Vec<u32>, m: u32, a: u32) {
+    let mut carry = a as u64;
+    for d in mag.iter_mut() {
+        let t = *d as u64 * m as u64 + carry;
+        *d = t as u32;
+        carry = t >> 32;
+    }
+    if carry > 0 {
+        mag.push(carry as u32);
+    }
+}
+
+// mag = mag / d, returns the remainder
+fn div_small(mag: &mut Vec<u32>, d: u32) -> u32 {
+    let mut rem = 0u64;
+    for x in mag.iter_mut().rev() {
+        let t = (rem << 32) | *x as u64;
+        *x = (t / d as u64) as u32;
+        rem = t % d as u64;
+    }
+    trim(mag);
+    rem as u32
+}
+
+// shift left by s < 32 bits, the result has one more digit
+fn shl_bits(a: &[u32], s: u32) -> Vec<u32> {
+    let mut out = Vec::with_capacity(a.len() + 1);
+    let mut carry = 0u32;
+    for &d in a {
+        out.push((d << s) | carry);
+        carry = if s == 0 { 0 } else { d >> (32 - s) };
+    }
+    out.push(carry);
+    out
+}
+
+// long division of magnitudes, returns (quotient, remainder)
+// Knuth, The Art of Computer Programming Vol. 2, Algorithm D
+// Note: b must not be zero
+fn div_rem_mag(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if cmp_mag(a, b) == Ordering::Less {
+        return (vec![], a.to_vec());
+    }
+    if b.len() == 1 {
+        let mut q = a.to_vec();
+        let r = div_small(&mut q, b[0]);
+        let mut r = vec![r];
+        trim(&mut r);
+        return (q, r);
+    }
+    // normalize so that the top digit of the divisor has its high bit set
+    // then the estimated quotient digit is at most 2 too large
+    let s = b[b.len() - 1].leading_zeros();
+    let mut v = shl_bits(b, s);
+    v.pop();
+    let mut u = shl_bits(a, s);
+    let n = v.len();
+    let m = a.len() - n;
+    let base = 1u64 << 32;
+    let (vtop, vnext) = (v[n - 1] as u64, v[n - 2] as u64);
+    let mut q = vec![0u32; m + 1];
+    for j in (0..=m).rev() {
+        // estimate the quotient digit from the top two digits
+        let num = ((u[j + n] as u64) << 32) | u[j + n - 1] as u64;
+        let mut qhat = num / vtop;
+        let mut rhat = num % vtop;
+        while qhat >= base || qhat * vnext > ((rhat << 32) | u[j + n - 2] as u64) {
+            qhat -= 1;
+            rhat += vtop;
+            if rhat >= base {
+                break;
+            }
+        }
+        // u[j..=j+n] -= qhat * v
+        let mut borrow = 0i64;
+        let mut carry = 0u64;
+        for i in 0..n {
+            let p = qhat * v[i] as u64 + carry;
+            carry = p >> 32;
+            let t = u[i + j] as i64 - borrow - (p & 0xffff_ffff) as i64;
+            u[i + j] = t as u32;
+            borrow = (t < 0) as i64;
+        }
+        let t = u[j + n] as i64 - borrow - carry as i64;
+        u[j + n] = t as u32;
+        // the estimate was one too large, add the divisor back
+        if t < 0 {
+            qhat -= 1;
+            let mut carry = 0u64;
+            for i in 0..n {
+                let s = u[i + j] as u64 + v[i] as u64 + carry;
+                u[i + j] = s as u32;
+                carry = s >> 32;
+            }
+            u[j + n] = u[j + n].wrapping_add(carry as u32);
+        }
+        q[j] = qhat as u32;
+    }
+    trim(&mut q);
+    // the remainder is what is left of u, shifted back
+    u.truncate(n);
+    let mut r: Vec<u32> = (0..n)
+        .map(|i| {
+            let high = if s == 0 || i + 1 == n {
+                0
+            } else {
+                u[i + 1] << (32 - s)
+            };
+            (u[i] >> s) | high
+        })
+        .collect();
+    trim(&mut r);
+    (q, r)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Complex;
+    use quickcheck::quickcheck;
+
+    fn big(s: &str) -> BigInt {
+        s.parse().unwrap()
+    }
+
+    // decimal digits round trip through Display and FromStr
+    #[test]
+    fn test_display_parse() {
+        assert_eq!("0", BigInt::zero().to_string());
+        assert_eq!("-42", BigInt::from(-42).to_string());
+        assert_eq!("0", big("-0").to_string());
+        let s = "-123456789012345678901234567890123456789";
+        assert_eq!(s, big(s).to_string());
+        assert_eq!("1000000000", BigInt::from(1_000_000_000).to_string());
+        assert_eq!("   +7", format!("{:+5}", BigInt::from(7)));
+        assert_eq!(Err(ParseNumberError::Empty), "-".parse::<BigInt>());
+        assert_eq!(Err(ParseNumberError::InvalidDigit), "12a".parse::<BigInt>());
+    }
+
+    // results that overflow i128
+    #[test]
+    fn test_large_arithmetic() {
+        let a = BigInt::from(i128::MAX);
+        let sq = a.clone() * a.clone();
+        assert_eq!(
+            "28948022309329048855892746252171976962977213799489202546401021394546514198529",
+            sq.to_string()
+        );
+        assert_eq!(a, sq.clone() / a.clone());
+        assert_eq!(BigInt::zero(), sq % a);
+        assert_eq!(
+            big("1267650600228229401496703205376"),
+            BigInt::from(2).pow(100)
+        );
+    }
+
+    // division by multi digit divisors, including the add back step
+    #[test]
+    fn test_div_rem() {
+        let a = big("340282366920938463463374607431768211455");
+        let b = big("18446744073709551617");
+        let (q, r) = a.div_rem(&b);
+        assert_eq!(big("18446744073709551615"), q);
+        assert_eq!(BigInt::zero(), r);
+        let (q, r) = BigInt::from(-7).div_rem(&BigInt::from(2));
+        assert_eq!((BigInt::from(-3), BigInt::from(-1)), (q, r));
+        // these divisors need the quotient estimate corrected
+        let a = big("79228162514264337589248983040");
+        let b = big("79228162514264337593543950335");
+        assert_eq!(BigInt::zero(), a.clone() / b.clone());
+        assert_eq!(BigInt::one(), b.clone() / a.clone());
+        let x = big("98765432109876543210987654321098765432109876543210");
+        let y = big("12345678901234567890123");
+        let (q, r) = x.div_rem(&y);
+        assert_eq!(x, q * y.clone() + r.clone());
+        assert!(r < y);
+    }
+
+    // Note: panics like the primitive integers
+    #[test]
+    #[should_panic(expected = "attempt to divide by zero")]
+    fn test_div_by_zero() {
+        let _ = BigInt::one() / BigInt::zero();
+    }
+
+    #[test]
+    fn test_gcd_ord() {
+        let a = BigInt::from(2).pow(80) * BigInt::from(3);
+        let b = BigInt::from(2).pow(70) * BigInt::from(-9);
+        assert_eq!(BigInt::from(2).pow(70) * BigInt::from(3), a.gcd(&b));
+        assert!(b < a);
+        assert!(BigInt::from(-5) < BigInt::from(-4));
+        assert!(big("-100000000000000000000") < BigInt::from(i64::MIN));
+        assert_eq!(-1e30, big("-1000000000000000000000000000000").to_f64());
+    }
+
+    // Complex<BigInt> shares Display, parsing and the operators
+    #[test]
+    fn test_complex_bigint() {
+        let z: Complex<BigInt> = "12345678901234567890 - 98765432109876543210i"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            "12345678901234567890 - 98765432109876543210i",
+            z.to_string()
+        );
+        let sq = z.clone() * z.clone();
+        assert_eq!(
+            "-9602194792318244158175582987903978052000 - 2438652622740435904474927602222527053800i",
+            sq.to_string()
+        );
+        assert_eq!(z, sq / z.clone());
+    }
+
+    // same results as the i128 operators for values that fit
+    quickcheck! {
+        fn prop_matches_i128(a: i64, b: i64) -> bool {
+            let (x, y) = (BigInt::from(a), BigInt::from(b));
+            let (a, b) = (a as i128, b as i128);
+            let mut ok = x.clone() + y.clone() == BigInt::from(a + b)
+                && x.clone() - y.clone() == BigInt::from(a - b)
+                && x.clone() * y.clone() == BigInt::from(a * b)
+                && x.cmp(&y) == a.cmp(&b)
+                && x.to_string() == a.to_string();
+            if b != 0 {
+                ok = ok && x.clone() / y.clone() == BigInt::from(a / b) && x % y == BigInt::from(a % b);
+            }
+            ok
+        }
+
+        fn prop_div_rem(a: Vec<u32>, b: Vec<u32>) -> bool {
+            let x = BigInt::from_parts(false, a);
+            let y = BigInt::from_parts(false, b);
+            if y.is_zero() {
+                return true;
+            }
+            let (q, r) = x.div_rem(&y);
+            q * y.clone() + r.clone() == x && r < y
+        }
+    }
+}
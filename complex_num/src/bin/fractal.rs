@@ -0,0 +1,110 @@
+// Render Mandelbrot and Julia sets with the fractal module
+//   cargo run --release -p complex_num --bin fractal -- [options]
+// without --output the image is printed to the terminal with ANSI colors
+// options:
+//   --julia C        render the Julia set of C instead of the Mandelbrot set
+//   --center C       center of the viewport, e.g. "-0.745+0.186i"
+//   --zoom W         width of the viewport in the complex plane
+//   --size WxH       image size in pixels
+//   --iter N         iteration limit
+//   --threads N      number of render threads, 0 for one per core
+//   --no-smooth      color by whole iteration counts
+//   --output FILE    write FILE.ppm or FILE.png instead
+
+use complex_num::fractal::{render, Fractal, Options, Viewport};
+use complex_num::Complex;
+use std::env;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::process;
+use std::str::FromStr;
+
+struct Args {
+    fractal: Fractal,
+    options: Options,
+    output: Option<String>,
+}
+
+fn parse_args(args: &[String]) -> Result<Args, String> {
+    let mut fractal = Fractal::Mandelbrot;
+    let mut center: Option<Complex<f64>> = None;
+    let mut zoom: Option<f64> = None;
+    let mut size: Option<(usize, usize)> = None;
+    let mut output = None;
+    let mut options = Options::default();
+    let mut it = args.iter();
+    while let Some(arg) = it.next() {
+        let mut value = || it.next().ok_or(format!("missing value for {arg}"));
+        match arg.as_str() {
+            "--julia" => fractal = Fractal::Julia(parse(value()?, arg)?),
+            "--center" => center = Some(parse(value()?, arg)?),
+            "--zoom" => zoom = Some(parse(value()?, arg)?),
+            "--iter" => options.max_iter = parse(value()?, arg)?,
+            "--threads" => options.threads = parse(value()?, arg)?,
+            "--no-smooth" => options.smooth = false,
+            "--output" => output = Some(value()?.clone()),
+            "--size" => {
+                let v = value()?;
+                let (w, h) = v
+                    .split_once('x')
+                    .ok_or(format!("invalid value for {arg}: {v}"))?;
+                size = Some((parse(w, arg)?, parse(h, arg)?));
+            }
+            _ => return Err(format!("unknown argument {arg}")),
+        }
+    }
+    let mut viewport = match fractal {
+        Fractal::Mandelbrot => Viewport::mandelbrot(),
+        Fractal::Julia(_) => Viewport::julia(),
+    };
+    viewport.center = center.unwrap_or(viewport.center);
+    viewport.width = zoom.unwrap_or(viewport.width);
+    options.viewport = viewport;
+    // a terminal is much smaller than an image file
+    (options.width, options.height) = match (size, &output) {
+        (Some(size), _) => size,
+        (None, Some(_)) => (800, 600),
+        (None, None) => (80, 48),
+    };
+    Ok(Args {
+        fractal,
+        options,
+        output,
+    })
+}
+
+fn parse<T: FromStr>(value: &str, arg: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value for {arg}: {value}"))
+}
+
+fn write(args: &Args) -> io::Result<()> {
+    let image = render(&args.fractal, &args.options);
+    let mut out: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+    match &args.output {
+        Some(path) if path.ends_with(".png") => image.write_png(&mut out)?,
+        Some(_) => image.write_ppm(&mut out)?,
+        None => image.write_ansi(&mut out)?,
+    }
+    out.flush()
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args = match parse_args(&args) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("error: {e}");
+            eprintln!("usage: fractal [--julia C] [--center C] [--zoom W] [--size WxH] [--iter N] [--threads N] [--no-smooth] [--output FILE]");
+            process::exit(2);
+        }
+    };
+    if let Err(e) = write(&args) {
+        eprintln!("error: {e}");
+        process::exit(1);
+    }
+}
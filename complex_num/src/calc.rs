@@ -0,0 +1,407 @@
+// Expression evaluator for the calculator REPL in main.rs
+// grammar, lowest precedence first:
+//   line    = name "=" expr | expr
+//   expr    = term (("+" | "-") term)*
+//   term    = unary (("*" | "/") unary)*
+//   unary   = "-" unary | power
+//   power   = atom ("^" unary)?           right associative, 2^3^2 = 2^9
+//   atom    = number | number "i" | "i" | name | name "(" expr ")" | "(" expr ")"
+// numbers are f64, "4i" and "2.5e3j" are imaginary literals
+// and a bare i (or j) is the imaginary unit
+// every result is stored in the variable ans
+
+use complex_num::Complex;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+type C = Complex<f64>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CalcError {
+    // character that cannot start a token, with its byte offset
+    UnexpectedChar(char, usize),
+    // token in the wrong place, with its byte offset
+    UnexpectedToken(String, usize),
+    // input ended in the middle of an expression
+    UnexpectedEnd,
+    UnknownVariable(String),
+    UnknownFunction(String),
+    // function names cannot be assigned to
+    ReservedName(String),
+}
+
+impl Display for CalcError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Self::UnexpectedChar(c, at) => write!(f, "unexpected character '{c}' at byte {at}"),
+            Self::UnexpectedToken(t, at) => write!(f, "unexpected '{t}' at byte {at}"),
+            Self::UnexpectedEnd => write!(f, "unexpected end of input"),
+            Self::UnknownVariable(name) => write!(f, "unknown variable '{name}'"),
+            Self::UnknownFunction(name) => write!(f, "unknown function '{name}'"),
+            Self::ReservedName(name) => write!(f, "'{name}' cannot be assigned"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Real(f64),
+    Imag(f64),
+    Name(String),
+    Op(char),
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Self::Real(x) => write!(f, "{x}"),
+            Self::Imag(x) => write!(f, "{x}i"),
+            Self::Name(name) => write!(f, "{name}"),
+            Self::Op(c) => write!(f, "{c}"),
+        }
+    }
+}
+
+const FUNCTIONS: [&str; 12] = [
+    "abs", "arg", "exp", "conj", "ln", "sqrt", "sin", "cos", "tan", "re", "im", "norm",
+];
+
+fn apply(name: &str, z: C) -> Option<C> {
+    let real = |x: f64| Complex::new(x, 0.0);
+    let value = match name {
+        "abs" => real(z.norm()),
+        "arg" => real(z.arg()),
+        "exp" => z.exp(),
+        "conj" => z.conjugate(),
+        "ln" => z.ln(),
+        "sqrt" => z.sqrt(),
+        "sin" => z.sin(),
+        "cos" => z.cos(),
+        "tan" => z.tan(),
+        "re" => real(z.real),
+        "im" => real(z.imag),
+        "norm" => real(z.norm_sqr()),
+        _ => return None,
+    };
+    Some(value)
+}
+
+// split the input into tokens with their byte offsets
+fn tokenize(s: &str) -> Result<Vec<(Token, usize)>, CalcError> {
+    let b = s.as_bytes();
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < b.len() {
+        let c = b[i];
+        let start = i;
+        if c.is_ascii_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() || c == b'.' {
+            while i < b.len() && (b[i].is_ascii_digit() || b[i] == b'.') {
+                i += 1;
+            }
+            // exponent, only if digits follow so that 2e is not eaten
+            if i < b.len() && (b[i] == b'e' || b[i] == b'E') {
+                let mut k = i + 1;
+                if k < b.len() && (b[k] == b'+' || b[k] == b'-') {
+                    k += 1;
+                }
+                if k < b.len() && b[k].is_ascii_digit() {
+                    i = k;
+                    while i < b.len() && b[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                }
+            }
+            let x: f64 = s[start..i]
+                .parse()
+                .map_err(|_| CalcError::UnexpectedToken(s[start..i].to_string(), start))?;
+            // an i or j directly after the number, not starting a longer name
+            let is_unit = |k: usize| k < b.len() && (b[k] == b'i' || b[k] == b'j');
+            let name_follows =
+                |k: usize| k < b.len() && (b[k].is_ascii_alphanumeric() || b[k] == b'_');
+            if is_unit(i) && !name_follows(i + 1) {
+                i += 1;
+                tokens.push((Token::Imag(x), start));
+            } else {
+                tokens.push((Token::Real(x), start));
+            }
+        } else if c.is_ascii_alphabetic() || c == b'_' {
+            while i < b.len() && (b[i].is_ascii_alphanumeric() || b[i] == b'_') {
+                i += 1;
+            }
+            let name = &s[start..i];
+            if name == "i" || name == "j" {
+                tokens.push((Token::Imag(1.0), start));
+            } else {
+                tokens.push((Token::Name(name.to_string()), start));
+            }
+        } else if b"+-*/^()=".contains(&c) {
+            i += 1;
+            tokens.push((Token::Op(c as char), start));
+        } else {
+            let ch = s[start..].chars().next().unwrap_or('?');
+            return Err(CalcError::UnexpectedChar(ch, start));
+        }
+    }
+    Ok(tokens)
+}
+
+// recursive descent over the token list, evaluating as it goes
+struct Parser<'a> {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    vars: &'a HashMap<String, C>,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(t, _)| t)
+    }
+
+    fn next(&mut self) -> Result<(Token, usize), CalcError> {
+        let t = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or(CalcError::UnexpectedEnd)?;
+        self.pos += 1;
+        Ok(t)
+    }
+
+    fn eat(&mut self, op: char) -> bool {
+        if self.peek() == Some(&Token::Op(op)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, op: char) -> Result<(), CalcError> {
+        match self.next()? {
+            (Token::Op(c), _) if c == op => Ok(()),
+            (t, at) => Err(CalcError::UnexpectedToken(t.to_string(), at)),
+        }
+    }
+
+    fn expr(&mut self) -> Result<C, CalcError> {
+        let mut value = self.term()?;
+        loop {
+            if self.eat('+') {
+                value += self.term()?;
+            } else if self.eat('-') {
+                value -= self.term()?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn term(&mut self) -> Result<C, CalcError> {
+        let mut value = self.unary()?;
+        loop {
+            if self.eat('*') {
+                value *= self.unary()?;
+            } else if self.eat('/') {
+                value /= self.unary()?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn unary(&mut self) -> Result<C, CalcError> {
+        if self.eat('-') {
+            return Ok(-self.unary()?);
+        }
+        if self.eat('+') {
+            return self.unary();
+        }
+        self.power()
+    }
+
+    fn power(&mut self) -> Result<C, CalcError> {
+        let base = self.atom()?;
+        if self.eat('^') {
+            let exponent = self.unary()?;
+            // whole real exponents stay exact, 2^3 = 8 and not 7.999...
+            if exponent.imag == 0.0 && exponent.real.fract() == 0.0 && exponent.real.abs() <= 1024.0
+            {
+                return Ok(base.powi(exponent.real as i32));
+            }
+            return Ok(base.powc(exponent));
+        }
+        Ok(base)
+    }
+
+    fn atom(&mut self) -> Result<C, CalcError> {
+        match self.next()? {
+            (Token::Real(x), _) => Ok(Complex::new(x, 0.0)),
+            (Token::Imag(x), _) => Ok(Complex::new(0.0, x)),
+            (Token::Op('('), _) => {
+                let value = self.expr()?;
+                self.expect(')')?;
+                Ok(value)
+            }
+            (Token::Name(name), _) if self.peek() == Some(&Token::Op('(')) => {
+                self.pos += 1;
+                let arg = self.expr()?;
+                self.expect(')')?;
+                apply(&name, arg).ok_or(CalcError::UnknownFunction(name))
+            }
+            (Token::Name(name), _) => self
+                .vars
+                .get(&name)
+                .copied()
+                .ok_or(CalcError::UnknownVariable(name)),
+            (t, at) => Err(CalcError::UnexpectedToken(t.to_string(), at)),
+        }
+    }
+}
+
+// calculator state, variables and the history of evaluated lines
+pub struct Calculator {
+    vars: HashMap<String, C>,
+    history: Vec<(String, C)>,
+}
+
+impl Calculator {
+    pub fn new() -> Self {
+        let mut vars = HashMap::new();
+        vars.insert("pi".to_string(), Complex::new(std::f64::consts::PI, 0.0));
+        vars.insert("e".to_string(), Complex::new(std::f64::consts::E, 0.0));
+        Self {
+            vars,
+            history: vec![],
+        }
+    }
+
+    // evaluate one line, either an expression or an assignment
+    pub fn eval(&mut self, line: &str) -> Result<C, CalcError> {
+        let mut tokens = tokenize(line)?;
+        let mut target = None;
+        if let [(Token::Name(name), _), (Token::Op('='), _), ..] = tokens.as_slice() {
+            if FUNCTIONS.contains(&name.as_str()) {
+                return Err(CalcError::ReservedName(name.clone()));
+            }
+            target = Some(name.clone());
+            tokens.drain(..2);
+        }
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            vars: &self.vars,
+        };
+        let value = parser.expr()?;
+        if let Some((t, at)) = parser.tokens.get(parser.pos) {
+            return Err(CalcError::UnexpectedToken(t.to_string(), *at));
+        }
+        if let Some(name) = target {
+            self.vars.insert(name, value);
+        }
+        self.vars.insert("ans".to_string(), value);
+        self.history.push((line.trim().to_string(), value));
+        Ok(value)
+    }
+
+    // successfully evaluated lines with their results, oldest first
+    pub fn history(&self) -> &[(String, C)] {
+        &self.history
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use complex_num::assert_complex_eq;
+
+    fn eval(s: &str) -> C {
+        Calculator::new().eval(s).unwrap()
+    }
+
+    // literals and the imaginary unit
+    #[test]
+    fn test_literals() {
+        assert_eq!(Complex::new(3.0, 4.0), eval("3+4i"));
+        assert_eq!(Complex::new(0.0, 1.0), eval("i"));
+        assert_eq!(Complex::new(2.0, -2500.0), eval("2 - 2.5e3j"));
+        assert_eq!(Complex::new(0.0, -1.0), eval("-i"));
+    }
+
+    // precedence, associativity and parentheses
+    #[test]
+    fn test_precedence() {
+        assert_eq!(Complex::new(7.0, 0.0), eval("1 + 2 * 3"));
+        assert_eq!(Complex::new(9.0, 0.0), eval("(1 + 2) * 3"));
+        assert_eq!(Complex::new(1.0, 0.0), eval("8 / 4 / 2"));
+        assert_eq!(Complex::new(512.0, 0.0), eval("2 ^ 3 ^ 2"));
+        assert_eq!(Complex::new(-4.0, 0.0), eval("-2^2"));
+        assert_eq!(Complex::new(-1.0, 0.0), eval("i^2"));
+    }
+
+    // functions, including the example from main.rs
+    #[test]
+    fn test_functions() {
+        // (3+4i)(2+i) / 5 = (2 + 11i) / 5
+        assert_complex_eq!(
+            Complex::new(0.4, 2.2),
+            eval("(3+4i) * conj(2-i) / 5"),
+            abs_tol = 1e-12,
+            rel_tol = 0.0
+        );
+        assert_eq!(Complex::new(5.0, 0.0), eval("abs(3 + 4i)"));
+        assert_complex_eq!(
+            Complex::new(std::f64::consts::FRAC_PI_2, 0.0),
+            eval("arg(i)"),
+            abs_tol = 1e-12,
+            rel_tol = 0.0
+        );
+        assert_complex_eq!(
+            Complex::new(-1.0, 0.0),
+            eval("exp(i * pi)"),
+            abs_tol = 1e-12,
+            rel_tol = 0.0
+        );
+        assert_eq!(Complex::new(4.0, 0.0), eval("im(3 + 4i)"));
+    }
+
+    // variables, ans and history
+    #[test]
+    fn test_variables() {
+        let mut calc = Calculator::new();
+        assert_eq!(Ok(Complex::new(1.0, 2.0)), calc.eval("z = 1 + 2i"));
+        assert_eq!(Ok(Complex::new(2.0, 4.0)), calc.eval("z * 2"));
+        assert_eq!(Ok(Complex::new(3.0, 6.0)), calc.eval("ans + z"));
+        assert_eq!(Ok(Complex::new(3.0, 6.0)), calc.eval("value_1 = ans"));
+        assert!(calc.eval("w").is_err());
+        assert_eq!(4, calc.history().len());
+        assert_eq!("z * 2", calc.history()[1].0);
+    }
+
+    // errors point at the problem
+    #[test]
+    fn test_errors() {
+        let mut calc = Calculator::new();
+        assert_eq!(Err(CalcError::UnexpectedEnd), calc.eval("1 +"));
+        assert_eq!(Err(CalcError::UnexpectedEnd), calc.eval(""));
+        assert_eq!(Err(CalcError::UnexpectedChar('#', 2)), calc.eval("1 # 2"));
+        assert_eq!(
+            Err(CalcError::UnexpectedToken(")".to_string(), 2)),
+            calc.eval("1 )")
+        );
+        assert_eq!(
+            Err(CalcError::UnknownVariable("x".to_string())),
+            calc.eval("x + 1")
+        );
+        assert_eq!(
+            Err(CalcError::UnknownFunction("foo".to_string())),
+            calc.eval("foo(1)")
+        );
+        assert_eq!(
+            Err(CalcError::ReservedName("abs".to_string())),
+            calc.eval("abs = 1")
+        );
+        assert!(calc.history().is_empty());
+    }
+}
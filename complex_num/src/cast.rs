@@ -0,0 +1,104 @@
+// Zero-copy views between Complex slices and interleaved scalar slices
+// Complex is #[repr(C)] with two fields of the same type, so it has
+// the layout of [T; 2]: real part first, imaginary part second,
+// no padding and the alignment of T
+// a slice of n Complex<T> is therefore exactly a slice of 2n T laid out
+// as re, im, re, im, ... which is the layout of raw I/Q sample buffers
+// these views work for any T and never copy
+
+use crate::Complex;
+use std::mem::{align_of, size_of};
+use std::slice;
+
+impl<T> Complex<T> {
+    // view Complex numbers as interleaved re, im scalars
+    pub fn as_interleaved(values: &[Self]) -> &[T] {
+        check_layout::<T>();
+        // Safety: Complex<T> has the layout of [T; 2] (checked above),
+        // the pointer and length describe the same bytes
+        unsafe { slice::from_raw_parts(values.as_ptr().cast::<T>(), values.len() * 2) }
+    }
+
+    pub fn as_interleaved_mut(values: &mut [Self]) -> &mut [T] {
+        check_layout::<T>();
+        // Safety: same as as_interleaved, the borrow is exclusive
+        unsafe { slice::from_raw_parts_mut(values.as_mut_ptr().cast::<T>(), values.len() * 2) }
+    }
+
+    // view interleaved re, im scalars as Complex numbers
+    // None if the number of scalars is odd
+    pub fn from_interleaved(values: &[T]) -> Option<&[Self]> {
+        check_layout::<T>();
+        if !values.len().is_multiple_of(2) {
+            return None;
+        }
+        // Safety: Complex<T> has the layout and alignment of [T; 2]
+        // and the length is even so no scalar is left over
+        Some(unsafe { slice::from_raw_parts(values.as_ptr().cast::<Self>(), values.len() / 2) })
+    }
+
+    pub fn from_interleaved_mut(values: &mut [T]) -> Option<&mut [Self]> {
+        check_layout::<T>();
+        if !values.len().is_multiple_of(2) {
+            return None;
+        }
+        // Safety: same as from_interleaved, the borrow is exclusive
+        Some(unsafe {
+            slice::from_raw_parts_mut(values.as_mut_ptr().cast::<Self>(), values.len() / 2)
+        })
+    }
+}
+
+// guaranteed by #[repr(C)], the check is optimized away
+fn check_layout<T>() {
+    assert_eq!(size_of::<Complex<T>>(), 2 * size_of::<T>());
+    assert_eq!(align_of::<Complex<T>>(), align_of::<T>());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // layout is the same as [T; 2]
+    #[test]
+    fn test_layout() {
+        assert_eq!(size_of::<[f32; 2]>(), size_of::<Complex<f32>>());
+        assert_eq!(align_of::<f32>(), align_of::<Complex<f32>>());
+        assert_eq!(size_of::<[u8; 2]>(), size_of::<Complex<u8>>());
+        assert_eq!(size_of::<[f64; 2]>(), size_of::<Complex<f64>>());
+    }
+
+    // complex to interleaved and back
+    #[test]
+    fn test_interleaved() {
+        let samples = [Complex::new(1.0f32, 2.0), Complex::new(3.0, -4.0)];
+        let iq = Complex::as_interleaved(&samples);
+        assert_eq!(&[1.0, 2.0, 3.0, -4.0], iq);
+        assert_eq!(Some(&samples[..]), Complex::from_interleaved(iq));
+        let empty: &[Complex<f32>] = &[];
+        assert!(Complex::as_interleaved(empty).is_empty());
+    }
+
+    // odd number of scalars cannot be viewed as Complex
+    #[test]
+    fn test_odd_length() {
+        let iq = [1i16, 2, 3];
+        assert_eq!(None, Complex::from_interleaved(&iq));
+        assert_eq!(
+            Some(&[Complex::new(1i16, 2)][..]),
+            Complex::from_interleaved(&iq[..2])
+        );
+    }
+
+    // writes through one view are seen by the other
+    #[test]
+    fn test_interleaved_mut() {
+        let mut iq = [0.0f64; 4];
+        let samples = Complex::from_interleaved_mut(&mut iq).unwrap();
+        samples[1] = Complex::new(5.0, 6.0);
+        assert_eq!([0.0, 0.0, 5.0, 6.0], iq);
+        let mut samples = [Complex::new(1, 2), Complex::new(3, 4)];
+        Complex::as_interleaved_mut(&mut samples)[3] = 9;
+        assert_eq!(Complex::new(3, 9), samples[1]);
+    }
+}
@@ -0,0 +1,332 @@
+// Checked, wrapping and saturating arithmetic for integer Complex numbers
+// the plain operators (+, -, *, /) behave like the ones of the scalar type:
+// they panic on overflow in debug builds, wrap silently in release builds
+// and always panic when dividing by zero
+// the methods here make that behaviour explicit:
+// - checked_* return None instead of overflowing or dividing by zero
+// - wrapping_* wrap around at the boundary of the scalar type
+// - saturating_* clamp every intermediate step to the scalar range
+// - try_div tells apart division by zero and overflow
+
+use crate::{Complex, ComplexError, Int};
+
+impl<T: Int> Complex<T> {
+    // (a+bi) + (x+yi) = (a+x)+(b+y)i
+    pub fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        let real = self.real.checked_add(rhs.real)?;
+        let imag = self.imag.checked_add(rhs.imag)?;
+        Some(Self::new(real, imag))
+    }
+
+    // (a+bi) - (x+yi) = (a-x)+(b-y)i
+    pub fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+        let real = self.real.checked_sub(rhs.real)?;
+        let imag = self.imag.checked_sub(rhs.imag)?;
+        Some(Self::new(real, imag))
+    }
+
+    // (a+bi)(x+yi) = (ax-by) + (ay+bx)i
+    pub fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        let real = self
+            .real
+            .checked_mul(rhs.real)?
+            .checked_sub(self.imag.checked_mul(rhs.imag)?)?;
+        let imag = self
+            .real
+            .checked_mul(rhs.imag)?
+            .checked_add(self.imag.checked_mul(rhs.real)?)?;
+        Some(Self::new(real, imag))
+    }
+
+    // (a+bi)/(x+yi) = ((ax+by) + (bx-ay)i)/(x^2+y^2)
+    // None when dividing by zero or when any step overflows
+    pub fn checked_div(&self, rhs: &Self) -> Option<Self> {
+        self.try_div(rhs).ok()
+    }
+
+    // same as checked_div but reports why the division failed
+    pub fn try_div(&self, rhs: &Self) -> Result<Self, ComplexError> {
+        let zero = T::zero();
+        if rhs.real == zero && rhs.imag == zero {
+            return Err(ComplexError::DivisionByZero);
+        }
+        let divide = || {
+            let denominator = rhs
+                .real
+                .checked_mul(rhs.real)?
+                .checked_add(rhs.imag.checked_mul(rhs.imag)?)?;
+            let real = self
+                .real
+                .checked_mul(rhs.real)?
+                .checked_add(self.imag.checked_mul(rhs.imag)?)?;
+            let imag = self
+                .imag
+                .checked_mul(rhs.real)?
+                .checked_sub(self.real.checked_mul(rhs.imag)?)?;
+            Some(Self::new(
+                real.checked_div(denominator)?,
+                imag.checked_div(denominator)?,
+            ))
+        };
+        divide().ok_or(ComplexError::Overflow)
+    }
+
+    pub fn wrapping_add(&self, rhs: &Self) -> Self {
+        Self::new(
+            self.real.wrapping_add(rhs.real),
+            self.imag.wrapping_add(rhs.imag),
+        )
+    }
+
+    pub fn wrapping_sub(&self, rhs: &Self) -> Self {
+        Self::new(
+            self.real.wrapping_sub(rhs.real),
+            self.imag.wrapping_sub(rhs.imag),
+        )
+    }
+
+    pub fn wrapping_mul(&self, rhs: &Self) -> Self {
+        let real = self
+            .real
+            .wrapping_mul(rhs.real)
+            .wrapping_sub(self.imag.wrapping_mul(rhs.imag));
+        let imag = self
+            .real
+            .wrapping_mul(rhs.imag)
+            .wrapping_add(self.imag.wrapping_mul(rhs.real));
+        Self::new(real, imag)
+    }
+
+    // the denominator x^2+y^2 wraps as well, when it comes out as 0
+    // (a zero divisor, or e.g. 16^2 in i8) the result is 0
+    // instead of the panic of the primitive wrapping_div
+    pub fn wrapping_div(&self, rhs: &Self) -> Self {
+        let denominator = rhs
+            .real
+            .wrapping_mul(rhs.real)
+            .wrapping_add(rhs.imag.wrapping_mul(rhs.imag));
+        if denominator == T::zero() {
+            return Self::new(T::zero(), T::zero());
+        }
+        let real = self
+            .real
+            .wrapping_mul(rhs.real)
+            .wrapping_add(self.imag.wrapping_mul(rhs.imag));
+        let imag = self
+            .imag
+            .wrapping_mul(rhs.real)
+            .wrapping_sub(self.real.wrapping_mul(rhs.imag));
+        Self::new(
+            real.wrapping_div(denominator),
+            imag.wrapping_div(denominator),
+        )
+    }
+
+    pub fn saturating_add(&self, rhs: &Self) -> Self {
+        Self::new(
+            self.real.saturating_add(rhs.real),
+            self.imag.saturating_add(rhs.imag),
+        )
+    }
+
+    pub fn saturating_sub(&self, rhs: &Self) -> Self {
+        Self::new(
+            self.real.saturating_sub(rhs.real),
+            self.imag.saturating_sub(rhs.imag),
+        )
+    }
+
+    pub fn saturating_mul(&self, rhs: &Self) -> Self {
+        let real = self
+            .real
+            .saturating_mul(rhs.real)
+            .saturating_sub(self.imag.saturating_mul(rhs.imag));
+        let imag = self
+            .real
+            .saturating_mul(rhs.imag)
+            .saturating_add(self.imag.saturating_mul(rhs.real));
+        Self::new(real, imag)
+    }
+
+    // dividing by zero saturates instead of panicking like the
+    // primitive saturating_div: each part of self goes to the maximum
+    // or minimum of the scalar type by its sign, 0 / 0 stays 0
+    pub fn saturating_div(&self, rhs: &Self) -> Self {
+        let denominator = rhs
+            .real
+            .saturating_mul(rhs.real)
+            .saturating_add(rhs.imag.saturating_mul(rhs.imag));
+        let real = self
+            .real
+            .saturating_mul(rhs.real)
+            .saturating_add(self.imag.saturating_mul(rhs.imag));
+        let imag = self
+            .imag
+            .saturating_mul(rhs.real)
+            .saturating_sub(self.real.saturating_mul(rhs.imag));
+        if denominator == T::zero() {
+            return Self::new(saturate(self.real), saturate(self.imag));
+        }
+        Self::new(
+            real.saturating_div(denominator),
+            imag.saturating_div(denominator),
+        )
+    }
+}
+
+// the limit of x / 0 in the scalar range: max for x > 0, min for x < 0
+// Int has no constants for the limits, the maximum is found by
+// saturating 1, 3, 7, 15, ... which only stops growing at the maximum
+fn saturate<T: Int>(x: T) -> T {
+    let zero = T::zero();
+    let one = T::one();
+    if x == zero {
+        return zero;
+    }
+    let mut max = one;
+    loop {
+        let next = max.saturating_add(max).saturating_add(one);
+        if next == max {
+            break;
+        }
+        max = next;
+    }
+    if x > zero {
+        max
+    } else {
+        zero.saturating_sub(max).saturating_sub(one)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // checked operations return the same result as the operators
+    // when nothing overflows
+    #[test]
+    fn test_checked_in_range() {
+        let lhs = Complex::new(2, 5);
+        let rhs = Complex::new(4, 7);
+        assert_eq!(Some(Complex::new(6, 12)), lhs.checked_add(&rhs));
+        assert_eq!(Some(Complex::new(-2, -2)), lhs.checked_sub(&rhs));
+        assert_eq!(Some(Complex::new(-27, 34)), lhs.checked_mul(&rhs));
+        let lhs = Complex::new(20, 25);
+        let rhs = Complex::new(0, 5);
+        assert_eq!(Some(Complex::new(5, -4)), lhs.checked_div(&rhs));
+    }
+
+    // checked operations return None on overflow
+    #[test]
+    fn test_checked_overflow() {
+        let big = Complex::new(i32::MAX, 1);
+        let one = Complex::new(1, 0);
+        assert_eq!(None, big.checked_add(&one));
+        assert_eq!(None, Complex::new(i32::MIN, 0).checked_sub(&one));
+        assert_eq!(None, big.checked_mul(&Complex::new(2, 0)));
+        // the denominator 2 * 50000^2 does not fit into i32
+        let rhs = Complex::new(50_000, 50_000);
+        assert_eq!(None, one.checked_div(&rhs));
+        // unsigned numbers overflow below zero
+        assert_eq!(None, Complex::new(1u8, 1).checked_sub(&Complex::new(2, 0)));
+    }
+
+    // checked division by zero returns None instead of panicking
+    #[test]
+    fn test_checked_div_zero() {
+        let lhs = Complex::new(2, 5);
+        let rhs = Complex::new(0, 0);
+        assert_eq!(None, lhs.checked_div(&rhs));
+    }
+
+    // try_div reports the reason of the failure
+    #[test]
+    fn test_try_div() {
+        let lhs = Complex::new(20, 25);
+        assert_eq!(Ok(Complex::new(4, 5)), lhs.try_div(&Complex::new(5, 0)));
+        assert_eq!(
+            Err(ComplexError::DivisionByZero),
+            lhs.try_div(&Complex::new(0, 0))
+        );
+        assert_eq!(
+            Err(ComplexError::Overflow),
+            lhs.try_div(&Complex::new(50_000, 50_000))
+        );
+        assert_eq!("division by zero", ComplexError::DivisionByZero.to_string());
+    }
+
+    // wrapping operations wrap around like the scalar type
+    #[test]
+    fn test_wrapping() {
+        let big = Complex::new(i8::MAX, i8::MIN);
+        let one = Complex::new(1, 1);
+        assert_eq!(Complex::new(i8::MIN, -127), big.wrapping_add(&one));
+        assert_eq!(Complex::new(126, i8::MAX), big.wrapping_sub(&one));
+        // (127-128i)(1+i) = 255 - 1i wraps to -1 - 1i
+        assert_eq!(Complex::new(-1, -1), big.wrapping_mul(&one));
+        assert_eq!(
+            Complex::new(4, 5),
+            Complex::new(20i8, 25).wrapping_div(&Complex::new(5, 0))
+        );
+    }
+
+    // saturating operations clamp to the scalar range
+    #[test]
+    fn test_saturating() {
+        let big = Complex::new(i8::MAX, i8::MIN);
+        let one = Complex::new(1, 1);
+        assert_eq!(Complex::new(i8::MAX, -127), big.saturating_add(&one));
+        assert_eq!(Complex::new(126, i8::MIN), big.saturating_sub(&one));
+        assert_eq!(Complex::new(i8::MAX, -1), big.saturating_mul(&one));
+        assert_eq!(
+            Complex::new(0u8, 0),
+            Complex::new(1u8, 1).saturating_sub(&Complex::new(2, 2))
+        );
+        assert_eq!(
+            Complex::new(4, 5),
+            Complex::new(20i8, 25).saturating_div(&Complex::new(5, 0))
+        );
+    }
+
+    // saturating division by zero goes to the limits of the scalar type
+    #[test]
+    fn test_saturating_div_zero() {
+        assert_eq!(
+            Complex::new(i8::MAX, i8::MIN),
+            Complex::new(1i8, -1).saturating_div(&Complex::new(0, 0))
+        );
+        assert_eq!(
+            Complex::new(0, i64::MAX),
+            Complex::new(0i64, 5).saturating_div(&Complex::new(0, 0))
+        );
+        assert_eq!(
+            Complex::new(u16::MAX, 0),
+            Complex::new(3u16, 0).saturating_div(&Complex::new(0, 0))
+        );
+    }
+
+    // a divisor whose wrapped norm is 0 does not panic
+    #[test]
+    fn test_wrapping_div_zero_norm() {
+        // 16^2 = 256 wraps to 0 in i8
+        assert_eq!(
+            Complex::new(0, 0),
+            Complex::new(1i8, 0).wrapping_div(&Complex::new(16, 0))
+        );
+        // (2^16)^2 = 2^32 wraps to 0 in u32
+        assert_eq!(
+            Complex::new(0, 0),
+            Complex::new(7u32, 7).wrapping_div(&Complex::new(1 << 16, 0))
+        );
+        assert_eq!(
+            Complex::new(0, 0),
+            Complex::new(i8::MIN, 1).wrapping_div(&Complex::new(0, 0))
+        );
+        // a wrapped but non zero norm still divides: 17^2 wraps to 33
+        // and 66 * 17 wraps to 98, 98 / 33 = 2
+        assert_eq!(
+            Complex::new(2, 0),
+            Complex::new(66i8, 0).wrapping_div(&Complex::new(17, 0))
+        );
+    }
+}
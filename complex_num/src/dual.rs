@@ -0,0 +1,366 @@
+// Dual numbers a + bε with ε^2 = 0
+// evaluating a function at x + 1ε gives f(x) + f'(x)ε, because
+// f(x + ε) = f(x) + f'(x)ε + f''(x)ε^2/2 + ... and every ε^2 term is 0
+// this is forward mode automatic differentiation: the derivative is
+// exact (no finite difference step) and costs about as much as f itself
+// - +, -, *, / operators and mixed operators with a real scalar
+// - Num and Float are implemented, so any generic code written for
+//   Float scalars differentiates, including Complex<Dual<f64>>
+// - display in the same layout as Complex, e.g. 3 - 2ε
+
+use crate::fmt::{format_part, write_terms};
+use crate::{Float, Num};
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+// real is the value, dual the derivative carried along with it
+// ordering compares the real parts first
+#[derive(Debug, PartialEq, PartialOrd, Clone, Copy, Default)]
+pub struct Dual<T> {
+    pub real: T,
+    pub dual: T,
+}
+
+impl<T: Float> Dual<T> {
+    pub fn new(real: T, dual: T) -> Self {
+        Self { real, dual }
+    }
+
+    // a number that does not depend on the input, derivative 0
+    pub fn constant(x: T) -> Self {
+        Self::new(x, T::zero())
+    }
+
+    // the input variable itself, derivative 1
+    pub fn variable(x: T) -> Self {
+        Self::new(x, T::one())
+    }
+
+    // f'(x) for any function written in terms of Dual numbers
+    pub fn derivative<F: Fn(Self) -> Self>(f: F, x: T) -> T {
+        f(Self::variable(x)).dual
+    }
+
+    // gradient of a function of several variables, one pass per variable
+    pub fn gradient<F: Fn(&[Self]) -> Self>(f: F, x: &[T]) -> Vec<T> {
+        (0..x.len())
+            .map(|i| {
+                let args: Vec<Self> = x
+                    .iter()
+                    .enumerate()
+                    .map(|(j, &v)| {
+                        if i == j {
+                            Self::variable(v)
+                        } else {
+                            Self::constant(v)
+                        }
+                    })
+                    .collect();
+                f(&args).dual
+            })
+            .collect()
+    }
+
+    // apply a real function with known derivative, chain rule:
+    // g(a + bε) = g(a) + g'(a)bε
+    fn chain(self, value: T, derivative: T) -> Self {
+        Self::new(value, derivative * self.dual)
+    }
+}
+
+impl<T: Float> Add for Dual<T> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.real + rhs.real, self.dual + rhs.dual)
+    }
+}
+
+impl<T: Float> Sub for Dual<T> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(self.real - rhs.real, self.dual - rhs.dual)
+    }
+}
+
+// (a + bε)(c + dε) = ac + (ad + bc)ε, the product rule
+impl<T: Float> Mul for Dual<T> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self::new(
+            self.real * rhs.real,
+            self.real * rhs.dual + self.dual * rhs.real,
+        )
+    }
+}
+
+// (a + bε)/(c + dε) = a/c + ((bc - ad)/c^2)ε, the quotient rule
+impl<T: Float> Div for Dual<T> {
+    type Output = Self;
+    fn div(self, rhs: Self) -> Self::Output {
+        Self::new(
+            self.real / rhs.real,
+            (self.dual * rhs.real - self.real * rhs.dual) / (rhs.real * rhs.real),
+        )
+    }
+}
+
+impl<T: Float> Neg for Dual<T> {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Self::new(-self.real, -self.dual)
+    }
+}
+
+// mixed operators with a real scalar on the right hand side
+impl<T: Float> Add<T> for Dual<T> {
+    type Output = Self;
+    fn add(self, rhs: T) -> Self::Output {
+        self + Self::constant(rhs)
+    }
+}
+
+impl<T: Float> Sub<T> for Dual<T> {
+    type Output = Self;
+    fn sub(self, rhs: T) -> Self::Output {
+        self - Self::constant(rhs)
+    }
+}
+
+impl<T: Float> Mul<T> for Dual<T> {
+    type Output = Self;
+    fn mul(self, rhs: T) -> Self::Output {
+        Self::new(self.real * rhs, self.dual * rhs)
+    }
+}
+
+impl<T: Float> Div<T> for Dual<T> {
+    type Output = Self;
+    fn div(self, rhs: T) -> Self::Output {
+        Self::new(self.real / rhs, self.dual / rhs)
+    }
+}
+
+// assignment operators for Dual and scalar right hand sides
+macro_rules! impl_assign_op {
+    ($($imp:ident, $method:ident, $op:ident, $op_method:ident;)*) => {
+        $(
+            impl<T: Float> $imp for Dual<T> {
+                fn $method(&mut self, rhs: Self) {
+                    *self = $op::$op_method(*self, rhs);
+                }
+            }
+
+            impl<T: Float> $imp<T> for Dual<T> {
+                fn $method(&mut self, rhs: T) {
+                    *self = $op::$op_method(*self, rhs);
+                }
+            }
+        )*
+    };
+}
+
+impl_assign_op! {
+    AddAssign, add_assign, Add, add;
+    SubAssign, sub_assign, Sub, sub;
+    MulAssign, mul_assign, Mul, mul;
+    DivAssign, div_assign, Div, div;
+}
+
+impl<T: Float> Num for Dual<T> {
+    fn zero() -> Self {
+        Self::constant(T::zero())
+    }
+    fn one() -> Self {
+        Self::constant(T::one())
+    }
+    fn to_f64(self) -> f64 {
+        self.real.to_f64()
+    }
+    // a step function, the derivative is 0 almost everywhere
+    fn trunc(self) -> Self {
+        Self::constant(self.real.trunc())
+    }
+}
+
+// every function is the real function of the value part
+// together with its derivative for the dual part
+impl<T: Float> Float for Dual<T> {
+    fn pi() -> Self {
+        Self::constant(T::pi())
+    }
+    fn from_f64(x: f64) -> Self {
+        Self::constant(T::from_f64(x))
+    }
+    fn epsilon() -> Self {
+        Self::constant(T::epsilon())
+    }
+    fn is_finite(self) -> bool {
+        self.real.is_finite() && self.dual.is_finite()
+    }
+    // d|x| = sign(x)
+    fn abs(self) -> Self {
+        self.chain(self.real.abs(), T::one().copysign(self.real))
+    }
+    // d sqrt(x) = 1 / (2 sqrt(x))
+    fn sqrt(self) -> Self {
+        let s = self.real.sqrt();
+        self.chain(s, T::one() / (s + s))
+    }
+    // d e^x = e^x
+    fn exp(self) -> Self {
+        let e = self.real.exp();
+        self.chain(e, e)
+    }
+    // d ln(x) = 1 / x
+    fn ln(self) -> Self {
+        self.chain(self.real.ln(), T::one() / self.real)
+    }
+    // d x^y = y x^(y-1) dx + x^y ln(x) dy
+    // the second term is left out for a constant exponent
+    // so that negative bases work like with powf on the reals
+    fn powf(self, n: Self) -> Self {
+        let value = self.real.powf(n.real);
+        let mut dual = n.real * self.real.powf(n.real - T::one()) * self.dual;
+        if n.dual != T::zero() {
+            dual = dual + value * self.real.ln() * n.dual;
+        }
+        Self::new(value, dual)
+    }
+    fn sin(self) -> Self {
+        self.chain(self.real.sin(), self.real.cos())
+    }
+    fn cos(self) -> Self {
+        self.chain(self.real.cos(), -self.real.sin())
+    }
+    fn sinh(self) -> Self {
+        self.chain(self.real.sinh(), self.real.cosh())
+    }
+    fn cosh(self) -> Self {
+        self.chain(self.real.cosh(), self.real.sinh())
+    }
+    // atan2(y, x) changes by (x dy - y dx) / (x^2 + y^2)
+    fn atan2(self, other: Self) -> Self {
+        let (y, x) = (self.real, other.real);
+        Self::new(
+            y.atan2(x),
+            (x * self.dual - y * other.dual) / (x * x + y * y),
+        )
+    }
+    // hypot(a, b) changes by (a da + b db) / hypot(a, b)
+    fn hypot(self, other: Self) -> Self {
+        let h = self.real.hypot(other.real);
+        Self::new(h, (self.real * self.dual + other.real * other.dual) / h)
+    }
+    // the sign of the other number is a step function, derivative 0
+    fn copysign(self, sign: Self) -> Self {
+        let value = self.real.copysign(sign.real);
+        let flip = if value == self.real {
+            T::one()
+        } else {
+            -T::one()
+        };
+        self.chain(value, flip)
+    }
+    // the larger distance of the values and of the derivatives, so two
+    // numbers with different derivatives are not approximately equal
+    fn ulps_between(self, other: Self) -> Option<u64> {
+        let real = self.real.ulps_between(other.real)?;
+        let dual = self.dual.ulps_between(other.dual)?;
+        Some(real.max(dual))
+    }
+}
+
+// same flags as Complex: precision, sign, width and alignment
+impl<T: Float> Display for Dual<T> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let zero = T::zero();
+        let terms = [
+            (self.real != zero, format_part(f, &self.real), ""),
+            (self.dual != zero, format_part(f, &self.dual), "ε"),
+        ];
+        write_terms(f, &terms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Complex;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-12
+    }
+
+    // product and quotient rule
+    #[test]
+    fn test_arithmetic() {
+        let x = Dual::variable(3.0);
+        assert_eq!(Dual::new(9.0, 6.0), x * x);
+        assert_eq!(Dual::new(1.0 / 3.0, -1.0 / 9.0), Dual::<f64>::one() / x);
+        assert_eq!(Dual::new(7.0, 2.0), x * 2.0 + 1.0);
+        let mut y = x;
+        y *= x;
+        y -= 1.0;
+        assert_eq!(Dual::new(8.0, 6.0), y);
+    }
+
+    // compare with the derivatives worked out by hand
+    #[test]
+    fn test_derivative() {
+        let d = Dual::derivative(|x| x * x.sin(), 2.0);
+        assert!(close(2.0f64.sin() + 2.0 * 2.0f64.cos(), d));
+        let d = Dual::derivative(|x| x.exp() / x, 1.5);
+        assert!(close(1.5f64.exp() * (1.5 - 1.0) / (1.5 * 1.5), d));
+        let d = Dual::derivative(|x| x.ln().sqrt(), 4.0);
+        assert!(close(1.0 / (2.0 * 4.0 * 4.0f64.ln().sqrt()), d));
+        let d = Dual::derivative(|x| x.powf(Dual::constant(3.0)), -2.0);
+        assert!(close(12.0, d));
+        let d = Dual::derivative(|x| x.powf(x), 2.0);
+        assert!(close(4.0 * (2.0f64.ln() + 1.0), d));
+        let d = Dual::derivative(|x| x.abs(), -3.0);
+        assert!(close(-1.0, d));
+    }
+
+    // partial derivatives of f(x, y) = x^2 y + atan2(y, x)
+    #[test]
+    fn test_gradient() {
+        let g = Dual::gradient(|v| v[0] * v[0] * v[1] + v[1].atan2(v[0]), &[1.0, 2.0]);
+        // df/dx = 2xy - y / (x^2 + y^2), df/dy = x^2 + x / (x^2 + y^2)
+        assert!(close(4.0 - 2.0 / 5.0, g[0]));
+        assert!(close(1.0 + 1.0 / 5.0, g[1]));
+    }
+
+    // the Complex functions differentiate through Dual parts:
+    // f(x) = |e^(ix)|^2 + Re(e^(ix)) = 1 + cos x
+    #[test]
+    fn test_complex_dual() {
+        let f = |x: Dual<f64>| {
+            let z = Complex::new(Dual::zero(), x).exp();
+            z.norm_sqr() + z.real
+        };
+        assert!(close(-(0.7f64.sin()), Dual::derivative(f, 0.7)));
+        let z = Complex::new(Dual::variable(1.0), Dual::constant(1.0));
+        // d/dx |x + i| = x / |x + i|
+        assert!(close(1.0 / 2.0f64.sqrt(), z.norm().dual));
+    }
+
+    // the derivative counts when comparing in ULPs
+    #[test]
+    fn test_ulps() {
+        let a = Dual::new(1.0, 2.0);
+        assert_eq!(Some(0), a.ulps_between(a));
+        assert_eq!(Some(1), a.ulps_between(Dual::new(1.0, 2.0f64.next_up())));
+        assert!(a.ulps_between(Dual::new(1.0, 3.0)).unwrap() > 1 << 50);
+        assert_eq!(None, a.ulps_between(Dual::new(1.0, f64::NAN)));
+        let z = Complex::new(a, Dual::zero());
+        assert!(!z.ulps_eq(&Complex::new(Dual::new(1.0, 3.0), Dual::zero()), 4));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!("3 + 2ε", Dual::new(3.0, 2.0).to_string());
+        assert_eq!("-1.50ε", format!("{:.2}", Dual::new(0.0, -1.5)));
+        assert_eq!("+2", format!("{:+}", Dual::constant(2.0)));
+        assert_eq!("  1 - 1ε", format!("{:>8}", Dual::new(1.0, -1.0)));
+    }
+}
@@ -0,0 +1,95 @@
+// Error type for fallible Complex operations
+// so that callers can handle bad input instead of panicking
+
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ComplexError {
+    // divisor was 0 + 0i
+    DivisionByZero,
+    // result does not fit into the scalar type
+    Overflow,
+}
+
+impl Display for ComplexError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Self::DivisionByZero => write!(f, "division by zero"),
+            Self::Overflow => write!(f, "arithmetic overflow"),
+        }
+    }
+}
+
+impl Error for ComplexError {}
+
+// Error returned when a string cannot be parsed into a Complex number
+// offset is the byte position in the input where the problem starts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseComplexError {
+    kind: ParseErrorKind,
+    offset: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    // input is empty or only whitespace
+    Empty,
+    // a real or imaginary part is not a valid number
+    InvalidNumber,
+    // second term of a + bi is missing the i (or j) suffix
+    MissingImaginaryUnit,
+    // first term of a + bi has an i (or j) suffix
+    UnexpectedImaginaryUnit,
+}
+
+impl ParseComplexError {
+    pub(crate) fn new(kind: ParseErrorKind, offset: usize) -> Self {
+        Self { kind, offset }
+    }
+
+    pub fn kind(&self) -> ParseErrorKind {
+        self.kind
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl Display for ParseComplexError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let msg = match self.kind {
+            ParseErrorKind::Empty => "empty string",
+            ParseErrorKind::InvalidNumber => "invalid number",
+            ParseErrorKind::MissingImaginaryUnit => "missing imaginary unit",
+            ParseErrorKind::UnexpectedImaginaryUnit => "unexpected imaginary unit",
+        };
+        write!(f, "{} at byte {}", msg, self.offset)
+    }
+}
+
+impl Error for ParseComplexError {}
+
+// Error returned when a string cannot be parsed into a BigInt or Rational
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseNumberError {
+    // input is empty or only a sign
+    Empty,
+    // a character that is not a decimal digit
+    InvalidDigit,
+    // rational with a zero denominator, "1/0"
+    ZeroDenominator,
+}
+
+impl Display for ParseNumberError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Self::Empty => write!(f, "cannot parse number from empty string"),
+            Self::InvalidDigit => write!(f, "invalid digit found in string"),
+            Self::ZeroDenominator => write!(f, "denominator is zero"),
+        }
+    }
+}
+
+impl Error for ParseNumberError {}
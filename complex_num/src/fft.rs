@@ -0,0 +1,428 @@
+// Fast Fourier Transform on floating point Complex numbers
+// - forward transform X[k] = sum x[n] e^(-2 pi i k n / N)
+// - inverse transform is normalized by 1/N so that inverse(forward(x)) == x
+// - radix-2 Cooley-Tukey for power of two lengths
+// - Bluestein's chirp z algorithm for every other length, it turns the
+//   transform into a convolution which is done with a radix-2 transform
+// - real input transform returning the N/2 + 1 non redundant bins
+// - FftPlanner caches plans (and so their twiddle factors) by length
+//   so transforming many buffers of the same length is cheap
+
+use crate::{Complex, Float};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+// A plan for transforms of one length
+// holds all the precomputed twiddle factors for that length
+#[derive(Debug)]
+pub struct Fft<T> {
+    len: usize,
+    algorithm: Algorithm<T>,
+}
+
+#[derive(Debug)]
+enum Algorithm<T> {
+    // twiddles[k] = e^(-2 pi i k / N) for k < N/2
+    Radix2 {
+        twiddles: Vec<Complex<T>>,
+    },
+    // chirp[k] = e^(-pi i k^2 / N)
+    // kernel is the forward transform of the conjugated chirp
+    // wrapped around to the length of the inner transform
+    Bluestein {
+        chirp: Vec<Complex<T>>,
+        kernel: Vec<Complex<T>>,
+        inner: Rc<Fft<T>>,
+    },
+}
+
+impl<T: Float> Fft<T> {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // transform buffer in place
+    // Note: panics if the buffer length is not the planned length
+    pub fn forward(&self, buffer: &mut [Complex<T>]) {
+        assert_eq!(self.len, buffer.len(), "buffer length does not match plan");
+        match &self.algorithm {
+            Algorithm::Radix2 { twiddles } => radix2(buffer, twiddles),
+            Algorithm::Bluestein {
+                chirp,
+                kernel,
+                inner,
+            } => bluestein(buffer, chirp, kernel, inner),
+        }
+    }
+
+    // inverse transform in place, normalized by 1/N
+    // uses ifft(x) = conj(fft(conj(x))) / N
+    pub fn inverse(&self, buffer: &mut [Complex<T>]) {
+        for x in buffer.iter_mut() {
+            *x = x.conjugate();
+        }
+        self.forward(buffer);
+        let scale = T::from_f64(1.0 / self.len as f64);
+        for x in buffer.iter_mut() {
+            *x = x.conjugate() * scale;
+        }
+    }
+}
+
+// Creates and caches plans
+// plans for the same length are shared through Rc
+#[derive(Debug)]
+pub struct FftPlanner<T> {
+    plans: HashMap<usize, Rc<Fft<T>>>,
+    real_twiddles: HashMap<usize, Rc<Vec<Complex<T>>>>,
+}
+
+impl<T: Float> Default for FftPlanner<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Float> FftPlanner<T> {
+    pub fn new() -> Self {
+        Self {
+            plans: HashMap::new(),
+            real_twiddles: HashMap::new(),
+        }
+    }
+
+    // plan for transforms of length len
+    // returns the cached plan if there is one
+    pub fn plan(&mut self, len: usize) -> Rc<Fft<T>> {
+        if let Some(plan) = self.plans.get(&len) {
+            return Rc::clone(plan);
+        }
+        let algorithm = if len.is_power_of_two() || len == 0 {
+            Algorithm::Radix2 {
+                twiddles: (0..len / 2).map(|k| twiddle(k, len)).collect(),
+            }
+        } else {
+            // the convolution of length 2N - 1 is done with a power of two
+            let m = (2 * len - 1).next_power_of_two();
+            let inner = self.plan(m);
+            let chirp: Vec<Complex<T>> = (0..len).map(|k| chirp(k, len)).collect();
+            let mut kernel = vec![Complex::new(T::zero(), T::zero()); m];
+            kernel[0] = chirp[0].conjugate();
+            for k in 1..len {
+                kernel[k] = chirp[k].conjugate();
+                kernel[m - k] = chirp[k].conjugate();
+            }
+            inner.forward(&mut kernel);
+            Algorithm::Bluestein {
+                chirp,
+                kernel,
+                inner,
+            }
+        };
+        let plan = Rc::new(Fft { len, algorithm });
+        self.plans.insert(len, Rc::clone(&plan));
+        plan
+    }
+
+    // forward transform of real input
+    // returns the bins 0..=N/2, the others are their conjugates
+    // even lengths are packed into a complex transform of half the length
+    pub fn rfft(&mut self, input: &[T]) -> Vec<Complex<T>> {
+        let n = input.len();
+        if n % 2 == 1 {
+            let mut buffer: Vec<Complex<T>> =
+                input.iter().map(|&x| Complex::new(x, T::zero())).collect();
+            self.plan(n).forward(&mut buffer);
+            buffer.truncate(n / 2 + 1);
+            return buffer;
+        }
+        if n == 0 {
+            return vec![];
+        }
+        // z[k] = x[2k] + i x[2k+1]
+        let half = n / 2;
+        let mut z: Vec<Complex<T>> = input
+            .chunks(2)
+            .map(|pair| Complex::new(pair[0], pair[1]))
+            .collect();
+        self.plan(half).forward(&mut z);
+        // split Z into the transforms of the even and odd samples
+        // E[k] = (Z[k] + conj(Z[N/2-k])) / 2
+        // O[k] = (Z[k] - conj(Z[N/2-k])) / 2i
+        // X[k] = E[k] + e^(-2 pi i k / N) O[k]
+        let twiddles = self.real_twiddles(n);
+        let half_scale = T::from_f64(0.5);
+        (0..=half)
+            .map(|k| {
+                let a = z[k % half];
+                let b = z[(half - k) % half].conjugate();
+                let e = (a + b) * half_scale;
+                let o = (a - b) * half_scale;
+                // dividing by i is multiplying by -i
+                let o = Complex::new(o.imag, -o.real);
+                e + twiddles[k] * o
+            })
+            .collect()
+    }
+
+    // inverse of rfft, len is the length of the original real signal
+    // Note: panics if spectrum does not have len / 2 + 1 bins
+    pub fn irfft(&mut self, spectrum: &[Complex<T>], len: usize) -> Vec<T> {
+        if len == 0 {
+            return vec![];
+        }
+        assert_eq!(
+            len / 2 + 1,
+            spectrum.len(),
+            "spectrum length does not match"
+        );
+        if len % 2 == 1 {
+            // rebuild the full spectrum using X[N-k] = conj(X[k])
+            let mut buffer = spectrum.to_vec();
+            for k in (1..=len / 2).rev() {
+                buffer.push(spectrum[k].conjugate());
+            }
+            self.plan(len).inverse(&mut buffer);
+            return buffer.iter().map(|x| x.real).collect();
+        }
+        // reverse the split done in rfft
+        // E[k] = (X[k] + conj(X[N/2-k])) / 2
+        // O[k] = (X[k] - conj(X[N/2-k])) e^(2 pi i k / N) / 2
+        // Z[k] = E[k] + i O[k]
+        let half = len / 2;
+        let twiddles = self.real_twiddles(len);
+        let half_scale = T::from_f64(0.5);
+        let mut z: Vec<Complex<T>> = (0..half)
+            .map(|k| {
+                let a = spectrum[k];
+                let b = spectrum[half - k].conjugate();
+                let e = (a + b) * half_scale;
+                let o = (a - b) * twiddles[k].conjugate() * half_scale;
+                e + Complex::new(-o.imag, o.real)
+            })
+            .collect();
+        self.plan(half).inverse(&mut z);
+        z.iter().flat_map(|x| [x.real, x.imag]).collect()
+    }
+
+    // e^(-2 pi i k / N) for k in 0..=N/2, used by the real transforms
+    fn real_twiddles(&mut self, len: usize) -> Rc<Vec<Complex<T>>> {
+        let twiddles = self
+            .real_twiddles
+            .entry(len)
+            .or_insert_with(|| Rc::new((0..=len / 2).map(|k| twiddle(k, len)).collect()));
+        Rc::clone(twiddles)
+    }
+}
+
+// forward transform with a temporary planner
+pub fn fft<T: Float>(buffer: &mut [Complex<T>]) {
+    FftPlanner::new().plan(buffer.len()).forward(buffer);
+}
+
+// inverse transform with a temporary planner
+pub fn ifft<T: Float>(buffer: &mut [Complex<T>]) {
+    FftPlanner::new().plan(buffer.len()).inverse(buffer);
+}
+
+// e^(-2 pi i k / n), the angle is computed in f64
+fn twiddle<T: Float>(k: usize, n: usize) -> Complex<T> {
+    let theta = -2.0 * std::f64::consts::PI * k as f64 / n as f64;
+    Complex::new(T::from_f64(theta.cos()), T::from_f64(theta.sin()))
+}
+
+// e^(-pi i k^2 / n)
+// k^2 is reduced mod 2n first since the angle repeats with period 2n
+// this keeps the angle small and accurate for large k
+fn chirp<T: Float>(k: usize, n: usize) -> Complex<T> {
+    let k2 = (k as u128 * k as u128 % (2 * n as u128)) as f64;
+    let theta = -std::f64::consts::PI * k2 / n as f64;
+    Complex::new(T::from_f64(theta.cos()), T::from_f64(theta.sin()))
+}
+
+// iterative in place radix-2 transform
+fn radix2<T: Float>(buffer: &mut [Complex<T>], twiddles: &[Complex<T>]) {
+    let n = buffer.len();
+    if n <= 1 {
+        return;
+    }
+    // reorder into bit reversed index order
+    let bits = n.trailing_zeros();
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+        if i < j {
+            buffer.swap(i, j);
+        }
+    }
+    // butterflies, the span doubles in every stage
+    let mut size = 2;
+    while size <= n {
+        let step = n / size;
+        for start in (0..n).step_by(size) {
+            for k in 0..size / 2 {
+                let a = buffer[start + k];
+                let b = buffer[start + k + size / 2] * twiddles[k * step];
+                buffer[start + k] = a + b;
+                buffer[start + k + size / 2] = a - b;
+            }
+        }
+        size *= 2;
+    }
+}
+
+// Bluestein's algorithm, nk = (k^2 + n^2 - (k-n)^2) / 2 turns the DFT into
+// X[k] = chirp[k] * sum (x[n] chirp[n]) conj(chirp[k-n])
+// which is a convolution done with the padded inner transform
+fn bluestein<T: Float>(
+    buffer: &mut [Complex<T>],
+    chirp: &[Complex<T>],
+    kernel: &[Complex<T>],
+    inner: &Fft<T>,
+) {
+    let mut a = vec![Complex::new(T::zero(), T::zero()); inner.len()];
+    for (k, x) in buffer.iter().enumerate() {
+        a[k] = *x * chirp[k];
+    }
+    inner.forward(&mut a);
+    for (x, y) in a.iter_mut().zip(kernel) {
+        *x *= *y;
+    }
+    inner.inverse(&mut a);
+    for (k, x) in buffer.iter_mut().enumerate() {
+        *x = a[k] * chirp[k];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_complex_eq;
+
+    // naive O(N^2) transform to compare against
+    fn dft(input: &[Complex<f64>]) -> Vec<Complex<f64>> {
+        let n = input.len();
+        (0..n)
+            .map(|k| {
+                input
+                    .iter()
+                    .enumerate()
+                    .map(|(j, x)| *x * twiddle::<f64>(k * j % n, n))
+                    .sum()
+            })
+            .collect()
+    }
+
+    // some input which is not symmetric in any way
+    fn signal(n: usize) -> Vec<Complex<f64>> {
+        (0..n)
+            .map(|k| {
+                let k = k as f64;
+                Complex::new((0.3 * k).sin() + k * 0.1, (1.7 * k).cos() - 0.5)
+            })
+            .collect()
+    }
+
+    fn assert_close(expected: &[Complex<f64>], result: &[Complex<f64>]) {
+        assert_eq!(expected.len(), result.len());
+        for (e, r) in expected.iter().zip(result) {
+            assert_complex_eq!(*e, *r, abs_tol = 1e-9, rel_tol = 0.0);
+        }
+    }
+
+    // power of two lengths use radix-2
+    #[test]
+    fn test_radix2_matches_dft() {
+        let mut planner = FftPlanner::new();
+        for n in [1, 2, 4, 8, 64] {
+            let input = signal(n);
+            let mut buffer = input.clone();
+            planner.plan(n).forward(&mut buffer);
+            assert_close(&dft(&input), &buffer);
+        }
+    }
+
+    // other lengths use Bluestein
+    #[test]
+    fn test_bluestein_matches_dft() {
+        let mut planner = FftPlanner::new();
+        for n in [3, 5, 6, 7, 12, 30, 97] {
+            let input = signal(n);
+            let mut buffer = input.clone();
+            planner.plan(n).forward(&mut buffer);
+            assert_close(&dft(&input), &buffer);
+        }
+    }
+
+    // inverse undoes forward
+    #[test]
+    fn test_roundtrip() {
+        for n in [1, 16, 15, 100] {
+            let input = signal(n);
+            let mut buffer = input.clone();
+            fft(&mut buffer);
+            ifft(&mut buffer);
+            assert_close(&input, &buffer);
+        }
+    }
+
+    // a single frequency ends up in a single bin
+    #[test]
+    fn test_single_tone() {
+        let n = 8;
+        let mut buffer: Vec<Complex<f64>> = (0..n).map(|k| twiddle(n - k, n)).collect();
+        fft(&mut buffer);
+        let mut expected = vec![Complex::new(0.0, 0.0); n];
+        expected[1] = Complex::new(n as f64, 0.0);
+        assert_close(&expected, &buffer);
+    }
+
+    // the real transform matches the complex one for even and odd lengths
+    #[test]
+    fn test_rfft() {
+        let mut planner = FftPlanner::new();
+        for n in [1, 2, 8, 10, 9, 31] {
+            let input: Vec<f64> = signal(n).iter().map(|x| x.real).collect();
+            let complex: Vec<Complex<f64>> = input.iter().map(|&x| Complex::new(x, 0.0)).collect();
+            let expected = dft(&complex);
+            let result = planner.rfft(&input);
+            assert_close(&expected[..n / 2 + 1], &result);
+            let back = planner.irfft(&result, n);
+            for (x, y) in input.iter().zip(&back) {
+                assert!((x - y).abs() < 1e-9);
+            }
+        }
+    }
+
+    // plans are cached by length
+    #[test]
+    fn test_planner_cache() {
+        let mut planner: FftPlanner<f64> = FftPlanner::new();
+        let a = planner.plan(12);
+        let b = planner.plan(12);
+        assert!(Rc::ptr_eq(&a, &b));
+        assert_eq!(12, a.len());
+        // Bluestein for 12 reuses the cached radix-2 plan of 32
+        assert!(Rc::ptr_eq(&planner.plan(32), &planner.plan(32)));
+    }
+
+    // f32 works too
+    #[test]
+    fn test_f32() {
+        let mut buffer = vec![Complex::new(1.0f32, 0.0); 6];
+        fft(&mut buffer);
+        assert!((buffer[0].real - 6.0).abs() < 1e-5);
+        assert!(buffer[1].norm() < 1e-5);
+    }
+
+    // wrong buffer length is a bug in the caller
+    #[test]
+    #[should_panic]
+    fn test_length_mismatch() {
+        let plan = FftPlanner::<f64>::new().plan(8);
+        plan.forward(&mut [Complex::new(0.0, 0.0); 4]);
+    }
+}
@@ -0,0 +1,441 @@
+// Polar form and transcendental functions for floating point Complex numbers
+// - modulus (norm) and argument (arg)
+// - conversion to and from polar form r∠θ
+// - exp, ln, sqrt and powers
+// - trigonometric and hyperbolic functions and their inverses
+//
+// Branch cuts of the multi valued functions follow the usual
+// principal value conventions (same as C99 <complex.h>):
+// - ln, sqrt, powf, powc: along the negative real axis (-inf, 0]
+// - asin, acos: along the real axis outside [-1, 1]
+// - atan: along the imaginary axis outside [-i, i]
+// - asinh: along the imaginary axis outside [-i, i]
+// - acosh: along the real axis (-inf, 1]
+// - atanh: along the real axis outside [-1, 1]
+// arg returns values in (-pi, pi] so points on a cut take the value
+// from the side of the sign of their imaginary part (including -0.0)
+
+use crate::{Complex, Float};
+
+// beyond this size of the imaginary part of tan (real part of tanh)
+// e^(-2x) is below the f64 epsilon, so cos 2a (cos 2b) no longer
+// changes the denominator and the short form is exact
+const LARGE: f64 = 20.0;
+
+impl<T: Float> Complex<T> {
+    // modulus |z| = sqrt(a^2 + b^2)
+    // hypot avoids the overflow of squaring large parts
+    pub fn norm(&self) -> T {
+        self.real.hypot(self.imag)
+    }
+
+    // argument (angle with positive real axis) in (-pi, pi]
+    pub fn arg(&self) -> T {
+        self.imag.atan2(self.real)
+    }
+
+    // create a Complex number from modulus r and argument theta
+    // r∠θ = r cos θ + r sin θ i
+    pub fn from_polar(r: T, theta: T) -> Self {
+        Self::new(r * theta.cos(), r * theta.sin())
+    }
+
+    // convert the number into (r, θ)
+    pub fn to_polar(&self) -> (T, T) {
+        (self.norm(), self.arg())
+    }
+
+    // e^(a+bi) = e^a (cos b + i sin b)
+    pub fn exp(&self) -> Self {
+        Self::from_polar(self.real.exp(), self.imag)
+    }
+
+    // principal natural logarithm ln|z| + i arg(z)
+    pub fn ln(&self) -> Self {
+        let (r, theta) = self.to_polar();
+        Self::new(r.ln(), theta)
+    }
+
+    // principal square root, real part is always >= 0
+    pub fn sqrt(&self) -> Self {
+        let zero = T::zero();
+        if self.real == zero && self.imag == zero {
+            return Self::new(zero, self.imag);
+        }
+        // t = sqrt((|z| + |a|) / 2) is computed without cancellation
+        // and the other part is recovered as b / 2t
+        let two = T::one() + T::one();
+        let t = ((self.norm() + self.real.abs()) / two).sqrt();
+        if self.real >= zero {
+            Self::new(t, self.imag / (two * t))
+        } else {
+            Self::new(self.imag.abs() / (two * t), t.copysign(self.imag))
+        }
+    }
+
+    // raise to an integer power using exponentiation by squaring
+    // negative powers are computed as 1 / z^|n|
+    pub fn powi(&self, n: i32) -> Self {
+        let mut base = self.copy();
+        let mut result = Self::new(T::one(), T::zero());
+        let mut e = n.unsigned_abs();
+        while e > 0 {
+            if e & 1 == 1 {
+                result *= base;
+            }
+            base *= base;
+            e >>= 1;
+        }
+        if n < 0 {
+            Self::new(T::one(), T::zero()) / result
+        } else {
+            result
+        }
+    }
+
+    // raise to a real power, z^n = r^n ∠ nθ
+    pub fn powf(&self, n: T) -> Self {
+        let zero = T::zero();
+        if self.real == zero && self.imag == zero {
+            return Self::zero_pow(n, zero);
+        }
+        let (r, theta) = self.to_polar();
+        Self::from_polar(r.powf(n), theta * n)
+    }
+
+    // raise to a complex power, z^w = e^(w ln z)
+    pub fn powc(&self, w: Self) -> Self {
+        let zero = T::zero();
+        if self.real == zero && self.imag == zero {
+            return Self::zero_pow(w.real, w.imag);
+        }
+        (w * self.ln()).exp()
+    }
+
+    // 0^w for w = a + bi, ln 0 does not exist so it is special cased
+    // 0^0 = 1, 0^w = 0 for a >= 0 and infinite for a < 0
+    // the angle of an infinite result is b ln 0, which is only defined
+    // for b = 0, otherwise the result is NaN
+    fn zero_pow(a: T, b: T) -> Self {
+        let zero = T::zero();
+        if a == zero && b == zero {
+            Self::new(T::one(), zero)
+        } else if a < zero && b == zero {
+            Self::new(T::from_f64(f64::INFINITY), zero)
+        } else if a >= zero {
+            Self::new(zero, zero)
+        } else {
+            let nan = T::from_f64(f64::NAN);
+            Self::new(nan, nan)
+        }
+    }
+
+    // sin(a+bi) = sin a cosh b + i cos a sinh b
+    pub fn sin(&self) -> Self {
+        let (a, b) = (self.real, self.imag);
+        Self::new(a.sin() * b.cosh(), a.cos() * b.sinh())
+    }
+
+    // cos(a+bi) = cos a cosh b - i sin a sinh b
+    pub fn cos(&self) -> Self {
+        let (a, b) = (self.real, self.imag);
+        Self::new(a.cos() * b.cosh(), -(a.sin() * b.sinh()))
+    }
+
+    // tan(a+bi) = (sin 2a + i sinh 2b) / (cos 2a + cosh 2b)
+    // for large |b| sinh 2b and cosh 2b overflow to inf / inf, there
+    // the quotient is 2 sin 2a e^(-2|b|) + i sign(b) to full precision
+    pub fn tan(&self) -> Self {
+        let two = T::one() + T::one();
+        let (a, b) = (two * self.real, two * self.imag);
+        if self.imag.abs() > T::from_f64(LARGE) {
+            let real = two * a.sin() * (-b.abs()).exp();
+            return Self::new(real, T::one().copysign(b));
+        }
+        let d = a.cos() + b.cosh();
+        Self::new(a.sin() / d, b.sinh() / d)
+    }
+
+    // sinh(a+bi) = sinh a cos b + i cosh a sin b
+    pub fn sinh(&self) -> Self {
+        let (a, b) = (self.real, self.imag);
+        Self::new(a.sinh() * b.cos(), a.cosh() * b.sin())
+    }
+
+    // cosh(a+bi) = cosh a cos b + i sinh a sin b
+    pub fn cosh(&self) -> Self {
+        let (a, b) = (self.real, self.imag);
+        Self::new(a.cosh() * b.cos(), a.sinh() * b.sin())
+    }
+
+    // tanh(a+bi) = (sinh 2a + i sin 2b) / (cosh 2a + cos 2b)
+    // for large |a| this is sign(a) + i 2 sin 2b e^(-2|a|), see tan
+    pub fn tanh(&self) -> Self {
+        let two = T::one() + T::one();
+        let (a, b) = (two * self.real, two * self.imag);
+        if self.real.abs() > T::from_f64(LARGE) {
+            let imag = two * b.sin() * (-a.abs()).exp();
+            return Self::new(T::one().copysign(a), imag);
+        }
+        let d = a.cosh() + b.cos();
+        Self::new(a.sinh() / d, b.sin() / d)
+    }
+
+    // asin(z) = -i ln(iz + sqrt(1 - z^2))
+    pub fn asin(&self) -> Self {
+        let one = Self::new(T::one(), T::zero());
+        let root = (one - self.copy() * self.copy()).sqrt();
+        (self.mul_i() + root).ln().mul_neg_i()
+    }
+
+    // acos(z) = -i ln(z + i sqrt(1 - z^2))
+    pub fn acos(&self) -> Self {
+        let one = Self::new(T::one(), T::zero());
+        let root = (one - self.copy() * self.copy()).sqrt();
+        (self.copy() + root.mul_i()).ln().mul_neg_i()
+    }
+
+    // atan(z) = i/2 (ln(1 - iz) - ln(1 + iz))
+    pub fn atan(&self) -> Self {
+        let one = Self::new(T::one(), T::zero());
+        let two = T::one() + T::one();
+        let iz = self.mul_i();
+        let d = (one.copy() - iz.copy()).ln() - (one + iz).ln();
+        Self::new(d.real / two, d.imag / two).mul_i()
+    }
+
+    // asinh(z) = ln(z + sqrt(z^2 + 1))
+    pub fn asinh(&self) -> Self {
+        let one = Self::new(T::one(), T::zero());
+        let root = (self.copy() * self.copy() + one).sqrt();
+        (self.copy() + root).ln()
+    }
+
+    // acosh(z) = ln(z + sqrt(z + 1) sqrt(z - 1))
+    // the product of two roots (instead of sqrt(z^2 - 1))
+    // keeps the cut on (-inf, 1] only
+    pub fn acosh(&self) -> Self {
+        let one = Self::new(T::one(), T::zero());
+        let root = (self.copy() + one.copy()).sqrt() * (self.copy() - one).sqrt();
+        (self.copy() + root).ln()
+    }
+
+    // atanh(z) = (ln(1 + z) - ln(1 - z)) / 2
+    pub fn atanh(&self) -> Self {
+        let one = Self::new(T::one(), T::zero());
+        let two = T::one() + T::one();
+        let d = (one.copy() + self.copy()).ln() - (one - self.copy()).ln();
+        Self::new(d.real / two, d.imag / two)
+    }
+
+    // multiply by i, (a+bi)i = -b + ai
+    fn mul_i(&self) -> Self {
+        Self::new(-self.imag, self.real)
+    }
+
+    // multiply by -i, (a+bi)(-i) = b - ai
+    fn mul_neg_i(&self) -> Self {
+        Self::new(self.imag, -self.real)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_complex_eq;
+    use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+    // 3 + 4i has modulus 5
+    #[test]
+    fn test_norm() {
+        let n = Complex::new(3.0, 4.0);
+        assert_eq!(5.0, n.norm());
+        assert_eq!(25.0, n.norm_sqr());
+    }
+
+    // arg of points on the axes
+    #[test]
+    fn test_arg() {
+        assert_eq!(0.0, Complex::new(1.0, 0.0).arg());
+        assert_eq!(FRAC_PI_2, Complex::new(0.0, 1.0).arg());
+        assert_eq!(PI, Complex::new(-1.0, 0.0).arg());
+        assert_eq!(-FRAC_PI_2, Complex::new(0.0, -1.0).arg());
+    }
+
+    // polar form round trip
+    #[test]
+    fn test_polar() {
+        let n = Complex::from_polar(2.0, FRAC_PI_4);
+        assert_complex_eq!(Complex::new(2f64.sqrt(), 2f64.sqrt()), n.copy());
+        let (r, theta) = n.to_polar();
+        assert!((r - 2.0).abs() < 1e-12);
+        assert!((theta - FRAC_PI_4).abs() < 1e-12);
+    }
+
+    // Euler's identity e^(i pi) = -1
+    #[test]
+    fn test_exp() {
+        assert_complex_eq!(Complex::new(-1.0, 0.0), Complex::new(0.0, PI).exp());
+        assert_complex_eq!(Complex::new(1.0, 2.0), Complex::new(1.0, 2.0).exp().ln());
+    }
+
+    // ln has its cut along the negative real axis
+    // the sign of a zero imaginary part selects the side
+    #[test]
+    fn test_ln_branch_cut() {
+        assert_complex_eq!(Complex::new(0.0, PI), Complex::new(-1.0, 0.0).ln());
+        assert_complex_eq!(Complex::new(0.0, -PI), Complex::new(-1.0, -0.0).ln());
+    }
+
+    // sqrt has its cut along the negative real axis
+    #[test]
+    fn test_sqrt() {
+        assert_complex_eq!(Complex::new(3.0, 4.0), Complex::new(-7.0, 24.0).sqrt());
+        assert_complex_eq!(Complex::new(0.0, 2.0), Complex::new(-4.0, 0.0).sqrt());
+        assert_complex_eq!(Complex::new(0.0, -2.0), Complex::new(-4.0, -0.0).sqrt());
+        assert_complex_eq!(Complex::new(0.0, 0.0), Complex::new(0.0, 0.0).sqrt());
+    }
+
+    // integer powers including negative ones
+    #[test]
+    fn test_powi() {
+        let i = Complex::new(0.0, 1.0);
+        assert_complex_eq!(Complex::new(-1.0, 0.0), i.powi(2));
+        assert_complex_eq!(Complex::new(1.0, 0.0), i.powi(4));
+        assert_complex_eq!(Complex::new(0.0, -1.0), i.powi(-1));
+        assert_complex_eq!(Complex::new(-7.0, 24.0), Complex::new(3.0, 4.0).powi(2));
+        assert_complex_eq!(Complex::new(1.0, 0.0), Complex::new(3.0, 4.0).powi(0));
+    }
+
+    // real and complex powers
+    #[test]
+    fn test_powf_powc() {
+        assert_complex_eq!(Complex::new(3.0, 4.0), Complex::new(-7.0, 24.0).powf(0.5));
+        // i^i = e^(-pi/2)
+        let i = Complex::new(0.0, 1.0);
+        assert_complex_eq!(Complex::new((-FRAC_PI_2).exp(), 0.0), i.powc(i.copy()));
+        assert_complex_eq!(Complex::new(0.0, 0.0), Complex::new(0.0, 0.0).powc(i));
+    }
+
+    // 0^0 = 1, negative powers of 0 are infinite
+    #[test]
+    fn test_zero_pow() {
+        let zero = Complex::new(0.0, 0.0);
+        assert_eq!(Complex::new(1.0, 0.0), zero.powf(0.0));
+        assert_eq!(Complex::new(1.0, 0.0), zero.powc(zero));
+        assert_eq!(Complex::new(0.0, 0.0), zero.powf(2.5));
+        assert_eq!(Complex::new(0.0, 0.0), zero.powc(Complex::new(2.0, -1.0)));
+        assert_eq!(Complex::new(f64::INFINITY, 0.0), zero.powf(-1.0));
+        assert_eq!(
+            Complex::new(f64::INFINITY, 0.0),
+            zero.powc(Complex::new(-0.5, 0.0))
+        );
+        let z = zero.powc(Complex::new(-1.0, 1.0));
+        assert!(z.real.is_nan() && z.imag.is_nan());
+        assert!(zero.powf(f64::NAN).real.is_nan());
+    }
+
+    // trig functions agree with the real valued ones on the real axis
+    // and satisfy sin^2 + cos^2 = 1 everywhere
+    #[test]
+    fn test_trig() {
+        let x = Complex::new(0.5, 0.0);
+        assert_complex_eq!(Complex::new(0.5f64.sin(), 0.0), x.sin());
+        assert_complex_eq!(Complex::new(0.5f64.cos(), 0.0), x.cos());
+        assert_complex_eq!(Complex::new(0.5f64.tan(), 0.0), x.tan());
+        let z = Complex::new(0.7, -1.3);
+        let s = z.sin();
+        let c = z.cos();
+        assert_complex_eq!(
+            Complex::new(1.0, 0.0),
+            s.copy() * s.copy() + c.copy() * c.copy(),
+        );
+        assert_complex_eq!(s / c, z.tan());
+    }
+
+    // hyperbolic functions, sinh(ix) = i sin(x)
+    #[test]
+    fn test_hyperbolic() {
+        let z = Complex::new(0.7, -1.3);
+        assert_complex_eq!(z.mul_i().sin(), z.sinh().mul_i());
+        assert_complex_eq!(z.mul_i().cos(), z.cosh());
+        assert_complex_eq!(z.sinh() / z.cosh(), z.tanh());
+    }
+
+    // tan and tanh of large arguments do not overflow to inf / inf
+    #[test]
+    fn test_tan_tanh_large() {
+        assert_complex_eq!(Complex::new(0.0, 1.0), Complex::new(1.0, 400.0).tan());
+        assert_complex_eq!(Complex::new(0.0, -1.0), Complex::new(1.0, -400.0).tan());
+        assert_complex_eq!(Complex::new(1.0, 0.0), Complex::new(400.0, 1.0).tanh());
+        assert_complex_eq!(Complex::new(-1.0, 0.0), Complex::new(-400.0, 1.0).tanh());
+        assert_complex_eq!(Complex::new(0.0, 1.0), Complex::new(1.0f32, 100.0).tan());
+        // just beyond the switch the tiny part still matches the
+        // quotient, which does not overflow yet
+        for z in [Complex::new(0.3, 20.5), Complex::new(-2.0, -25.0)] {
+            let (a, b) = (2.0 * z.real, 2.0 * z.imag);
+            let q = Complex::new(a.sin(), b.sinh()) / (a.cos() + b.cosh());
+            let t = z.tan();
+            assert!((t.real - q.real).abs() <= 1e-12 * q.real.abs(), "{t} {q}");
+            assert_eq!(q.imag, t.imag);
+            let h = z.mul_i().tanh();
+            assert_complex_eq!(t.mul_i(), h, abs_tol = 0.0, rel_tol = 1e-12);
+        }
+    }
+
+    // inverse functions undo the forward ones away from the cuts
+    #[test]
+    fn test_inverse() {
+        let z = Complex::new(0.3, 0.4);
+        assert_complex_eq!(z.copy(), z.asin().sin());
+        assert_complex_eq!(z.copy(), z.acos().cos());
+        assert_complex_eq!(z.copy(), z.atan().tan());
+        assert_complex_eq!(z.copy(), z.asinh().sinh());
+        assert_complex_eq!(z.copy(), z.acosh().cosh());
+        assert_complex_eq!(z.copy(), z.atanh().tanh());
+    }
+
+    // asin and acos jump across the real axis beyond 1
+    #[test]
+    fn test_asin_acos_branch_cut() {
+        let t = 2f64.acosh();
+        assert_complex_eq!(Complex::new(FRAC_PI_2, t), Complex::new(2.0, 1e-15).asin());
+        assert_complex_eq!(
+            Complex::new(FRAC_PI_2, -t),
+            Complex::new(2.0, -1e-15).asin(),
+        );
+        assert_complex_eq!(Complex::new(0.0, -t), Complex::new(2.0, 1e-15).acos());
+        assert_complex_eq!(Complex::new(0.0, t), Complex::new(2.0, -1e-15).acos());
+    }
+
+    // atan and asinh jump across the imaginary axis beyond i
+    #[test]
+    fn test_atan_asinh_branch_cut() {
+        let t = 3f64.ln() / 2.0;
+        assert_complex_eq!(Complex::new(FRAC_PI_2, t), Complex::new(1e-15, 2.0).atan());
+        assert_complex_eq!(
+            Complex::new(-FRAC_PI_2, t),
+            Complex::new(-1e-15, 2.0).atan(),
+        );
+        let t = 2f64.acosh();
+        assert_complex_eq!(Complex::new(t, FRAC_PI_2), Complex::new(1e-15, 2.0).asinh());
+        assert_complex_eq!(
+            Complex::new(-t, FRAC_PI_2),
+            Complex::new(-1e-15, 2.0).asinh(),
+        );
+    }
+
+    // acosh jumps across (-inf, 1] and atanh beyond 1
+    #[test]
+    fn test_acosh_atanh_branch_cut() {
+        let t = 2f64.acosh();
+        assert_complex_eq!(Complex::new(t, PI), Complex::new(-2.0, 1e-15).acosh());
+        assert_complex_eq!(Complex::new(t, -PI), Complex::new(-2.0, -1e-15).acosh());
+        let t = 3f64.ln() / 2.0;
+        assert_complex_eq!(Complex::new(t, FRAC_PI_2), Complex::new(2.0, 1e-15).atanh());
+        assert_complex_eq!(
+            Complex::new(t, -FRAC_PI_2),
+            Complex::new(2.0, -1e-15).atanh(),
+        );
+    }
+}
@@ -0,0 +1,243 @@
+// Formatting of Complex numbers
+// Display prints a + bi, dropping the zero parts ("2 - 3i", "3i", "0")
+// and honors the flags of the format spec:
+// - precision is applied to both parts, {:.3}
+// - sign flag prints + in front of a positive leading term, {:+}
+// - width, fill and alignment apply to the whole number, {:>20}, {:*^12}
+//   numbers are right aligned by default like the primitive numbers
+// - alternate flag prints the polar form r∠θ, {:#} or {:#.2}
+// the same layout is used by the other formatting traits:
+// - LowerExp and UpperExp, {:e} and {:E}
+// - LowerHex, UpperHex, Octal and Binary for integer parts, {:x}, {:#b}
+//   negative parts are printed as sign and magnitude ("-ff")
+//   instead of two's complement
+
+use crate::{Complex, Int, Num};
+use std::fmt::{
+    Alignment, Binary, Display, Formatter, LowerExp, LowerHex, Octal, Result as FmtResult,
+    UpperExp, UpperHex, Write,
+};
+
+impl<T: Num> Display for Complex<T> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        if f.alternate() {
+            // polar form, computed in f64 so that it works for any scalar
+            let (a, b) = (self.real.clone().to_f64(), self.imag.clone().to_f64());
+            let (r, theta) = (a.hypot(b), b.atan2(a));
+            let s = match f.precision() {
+                Some(p) => format!("{:.*}∠{:.*}", p, r, p, theta),
+                None => format!("{}∠{}", r, theta),
+            };
+            return pad(f, &s);
+        }
+        let (real, imag) = (format_part(f, &self.real), format_part(f, &self.imag));
+        write_rect(f, self, real, imag)
+    }
+}
+
+impl<T: Num + LowerExp> LowerExp for Complex<T> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let (real, imag) = match f.precision() {
+            Some(p) => (
+                format!("{:.*e}", p, self.real),
+                format!("{:.*e}", p, self.imag),
+            ),
+            None => (format!("{:e}", self.real), format!("{:e}", self.imag)),
+        };
+        write_rect(f, self, real, imag)
+    }
+}
+
+impl<T: Num + UpperExp> UpperExp for Complex<T> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let (real, imag) = match f.precision() {
+            Some(p) => (
+                format!("{:.*E}", p, self.real),
+                format!("{:.*E}", p, self.imag),
+            ),
+            None => (format!("{:E}", self.real), format!("{:E}", self.imag)),
+        };
+        write_rect(f, self, real, imag)
+    }
+}
+
+// radix formats differ only in the formatting trait used for the parts
+macro_rules! impl_radix_fmt {
+    ($($tr:ident => $plain:literal, $alt:literal;)*) => {
+        $(
+            impl<T: Int + $tr> $tr for Complex<T> {
+                fn fmt(&self, f: &mut Formatter) -> FmtResult {
+                    let part = |x: T| {
+                        // magnitude of a negative number is 0 - x,
+                        // for T::MIN it wraps back to T::MIN whose
+                        // bit pattern is exactly the magnitude
+                        let (sign, m) = if x < T::zero() {
+                            ("-", T::zero().wrapping_sub(x))
+                        } else {
+                            ("", x)
+                        };
+                        if f.alternate() {
+                            format!(concat!("{}", $alt), sign, m)
+                        } else {
+                            format!(concat!("{}", $plain), sign, m)
+                        }
+                    };
+                    write_rect(f, self, part(self.real), part(self.imag))
+                }
+            }
+        )*
+    };
+}
+
+impl_radix_fmt! {
+    LowerHex => "{:x}", "{:#x}";
+    UpperHex => "{:X}", "{:#X}";
+    Octal => "{:o}", "{:#o}";
+    Binary => "{:b}", "{:#b}";
+}
+
+// lay out already formatted parts as a + bi
+fn write_rect<T: Num>(f: &mut Formatter, n: &Complex<T>, real: String, imag: String) -> FmtResult {
+    let zero = T::zero();
+    let terms = [(n.real != zero, real, ""), (n.imag != zero, imag, "i")];
+    write_terms(f, &terms)
+}
+
+// lay out already formatted terms with their units, e.g. a + bi + cj + dk
+// zero terms are left out (all zero prints the first one), a negative
+// term turns the + in front of it into a - and the sign flag adds a
+// leading +, also used by Quaternion and Dual
+pub(crate) fn write_terms(f: &mut Formatter, terms: &[(bool, String, &str)]) -> FmtResult {
+    let plus = if f.sign_plus() { "+" } else { "" };
+    let mut s = String::new();
+    for (_, value, unit) in terms.iter().filter(|(non_zero, _, _)| *non_zero) {
+        if s.is_empty() {
+            write!(s, "{}{}", signed(plus, value), unit)?;
+        } else {
+            match value.strip_prefix('-') {
+                Some(m) => write!(s, " - {m}{unit}")?,
+                None => write!(s, " + {value}{unit}")?,
+            }
+        }
+    }
+    if s.is_empty() {
+        let (_, value, unit) = &terms[0];
+        write!(s, "{}{}", signed(plus, value), unit)?;
+    }
+    pad(f, &s)
+}
+
+// format a single part with the precision of the format spec
+pub(crate) fn format_part<T: Display>(f: &Formatter, x: &T) -> String {
+    match f.precision() {
+        Some(p) => format!("{:.*}", p, x),
+        None => format!("{}", x),
+    }
+}
+
+// prefix with the + of the sign flag unless already negative
+fn signed(plus: &str, s: &str) -> String {
+    if s.starts_with('-') {
+        s.to_string()
+    } else {
+        format!("{plus}{s}")
+    }
+}
+
+// apply width, fill and alignment to the whole formatted number
+// Formatter::pad cannot be used because it treats
+// the precision as the maximum length of the string
+fn pad(f: &mut Formatter, s: &str) -> FmtResult {
+    let len = s.chars().count();
+    let width = f.width().unwrap_or(0);
+    if len >= width {
+        return f.write_str(s);
+    }
+    let padding = width - len;
+    let (before, after) = match f.align() {
+        Some(Alignment::Left) => (0, padding),
+        Some(Alignment::Center) => (padding / 2, padding - padding / 2),
+        Some(Alignment::Right) | None => (padding, 0),
+    };
+    let fill = f.fill();
+    for _ in 0..before {
+        f.write_char(fill)?;
+    }
+    f.write_str(s)?;
+    for _ in 0..after {
+        f.write_char(fill)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // precision applies to both parts
+    #[test]
+    fn test_precision() {
+        let n = Complex::new(1.0, -2.5);
+        assert_eq!("1.000 - 2.500i", format!("{n:.3}"));
+        assert_eq!("0.00", format!("{:.2}", Complex::new(0.0, 0.0)));
+        // integers ignore precision
+        assert_eq!("2 + 3i", format!("{:.3}", Complex::new(2, 3)));
+    }
+
+    // sign flag affects the leading term only
+    #[test]
+    fn test_sign_plus() {
+        assert_eq!("+2 + 3i", format!("{:+}", Complex::new(2, 3)));
+        assert_eq!("-2 - 3i", format!("{:+}", Complex::new(-2, -3)));
+        assert_eq!("+3i", format!("{:+}", Complex::new(0, 3)));
+        assert_eq!("+0", format!("{:+}", Complex::new(0, 0)));
+    }
+
+    // width, fill and alignment apply to the whole number
+    #[test]
+    fn test_width() {
+        let n = Complex::new(2, 3);
+        assert_eq!("    2 + 3i", format!("{n:>10}"));
+        assert_eq!("    2 + 3i", format!("{n:10}"));
+        assert_eq!("2 + 3i    ", format!("{n:<10}"));
+        assert_eq!("**2 + 3i**", format!("{n:*^10}"));
+        assert_eq!("2 + 3i", format!("{n:3}"));
+        assert_eq!(
+            "   1.50 - 0.25i",
+            format!("{:>15.2}", Complex::new(1.5, -0.25))
+        );
+    }
+
+    // alternate flag prints the polar form
+    #[test]
+    fn test_polar() {
+        let n = Complex::new(3.0, 4.0);
+        assert_eq!("5.000∠0.927", format!("{n:#.3}"));
+        assert_eq!("1∠0", format!("{:#}", Complex::new(1, 0)));
+        assert_eq!("  2.0∠3.1", format!("{:>#9.1}", Complex::new(-2, 0)));
+    }
+
+    // scientific notation
+    #[test]
+    fn test_exp() {
+        let n = Complex::new(1500.0, -0.25);
+        assert_eq!("1.5e3 - 2.5e-1i", format!("{n:e}"));
+        assert_eq!("1.5E3 - 2.5E-1i", format!("{n:E}"));
+        assert_eq!("1.50e3 - 2.50e-1i", format!("{n:.2e}"));
+        assert_eq!("1.2e2 + 4e0i", format!("{:e}", Complex::new(120, 4)));
+    }
+
+    // radix formats use sign and magnitude
+    #[test]
+    fn test_radix() {
+        let n = Complex::new(255, -16);
+        assert_eq!("ff - 10i", format!("{n:x}"));
+        assert_eq!("FF - 10i", format!("{n:X}"));
+        assert_eq!("0xff - 0x10i", format!("{n:#x}"));
+        assert_eq!("377 - 20i", format!("{n:o}"));
+        assert_eq!("101 + 10i", format!("{:b}", Complex::new(5, 2)));
+        assert_eq!("0b101", format!("{:#b}", Complex::new(5, 0)));
+        assert_eq!("-80000000i", format!("{:x}", Complex::new(0, i32::MIN)));
+        assert_eq!("-1 + 1i", format!("{:x}", Complex::new(-1i8, 1)));
+    }
+}
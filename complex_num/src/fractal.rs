@@ -0,0 +1,403 @@
+// Mandelbrot and Julia set rendering
+// every pixel iterates z = z^2 + c with Complex<f64> until |z| escapes
+// or the iteration limit is hit, so this doubles as a stress test of Mul and Add
+// - Mandelbrot: c is the pixel, z starts at 0
+// - Julia: c is fixed, z starts at the pixel
+// - smooth coloring uses the fractional escape count n + 1 - log2(ln|z| / ln R)
+//   with R the bailout radius, which removes the bands between iteration counts
+// - rows are rendered by a pool of threads taking the next free row
+// - images are written as PPM, PNG or 24 bit ANSI colors for the terminal
+//   the encoders are written out here so the crate needs no dependencies
+
+use crate::Complex;
+use std::io::{self, Write};
+use std::sync::Mutex;
+use std::thread;
+
+// bailout radius, larger than 2 so the smooth count is accurate
+const ESCAPE_RADIUS: f64 = 256.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Fractal {
+    Mandelbrot,
+    Julia(Complex<f64>),
+}
+
+impl Fractal {
+    // number of iterations until the orbit escapes, None if it never does
+    // fractional when smooth is set, a whole number otherwise
+    pub fn escape_time(&self, point: Complex<f64>, max_iter: u32, smooth: bool) -> Option<f64> {
+        let (mut z, c) = match *self {
+            Self::Mandelbrot => (Complex::new(0.0, 0.0), point),
+            Self::Julia(c) => (point, c),
+        };
+        for n in 0..max_iter {
+            let r2 = z.norm_sqr();
+            if r2 > ESCAPE_RADIUS * ESCAPE_RADIUS {
+                if !smooth {
+                    return Some(n as f64);
+                }
+                // ln|z| = ln(|z|^2) / 2, between ln R and 2 ln R
+                // so the fraction stays between n and n + 1
+                let nu = (r2.ln() / 2.0 / ESCAPE_RADIUS.ln()).log2();
+                return Some(n as f64 + 1.0 - nu.clamp(0.0, 1.0));
+            }
+            z = z * z + c;
+        }
+        None
+    }
+}
+
+// region of the complex plane shown in the image
+// the height follows from the aspect ratio of the image
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    pub center: Complex<f64>,
+    pub width: f64,
+}
+
+impl Viewport {
+    // the whole Mandelbrot set
+    pub fn mandelbrot() -> Self {
+        Self {
+            center: Complex::new(-0.5, 0.0),
+            width: 3.5,
+        }
+    }
+
+    // the usual Julia sets fit into |z| < 2
+    pub fn julia() -> Self {
+        Self {
+            center: Complex::new(0.0, 0.0),
+            width: 4.0,
+        }
+    }
+
+    // complex number at the center of pixel (x, y), y grows downwards
+    pub fn point(&self, x: usize, y: usize, width: usize, height: usize) -> Complex<f64> {
+        let scale = self.width / width as f64;
+        let dx = (x as f64 + 0.5) - width as f64 / 2.0;
+        let dy = height as f64 / 2.0 - (y as f64 + 0.5);
+        self.center + Complex::new(dx * scale, dy * scale)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    pub width: usize,
+    pub height: usize,
+    pub viewport: Viewport,
+    pub max_iter: u32,
+    pub smooth: bool,
+    // 0 means one thread per available core
+    pub threads: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            width: 800,
+            height: 600,
+            viewport: Viewport::mandelbrot(),
+            max_iter: 256,
+            smooth: true,
+            threads: 0,
+        }
+    }
+}
+
+// RGB pixels stored row by row
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<[u8; 3]>,
+}
+
+impl Image {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn pixel(&self, x: usize, y: usize) -> [u8; 3] {
+        self.pixels[y * self.width + x]
+    }
+
+    // binary PPM (P6), the simplest format image viewers understand
+    pub fn write_ppm<W: Write>(&self, mut out: W) -> io::Result<()> {
+        write!(out, "P6\n{} {}\n255\n", self.width, self.height)?;
+        out.write_all(&self.pixels.concat())
+    }
+
+    // 8 bit RGB PNG with uncompressed (stored) deflate blocks
+    pub fn write_png<W: Write>(&self, mut out: W) -> io::Result<()> {
+        out.write_all(b"\x89PNG\r\n\x1a\n")?;
+        let mut ihdr = vec![];
+        ihdr.extend((self.width as u32).to_be_bytes());
+        ihdr.extend((self.height as u32).to_be_bytes());
+        // bit depth 8, color type RGB, default compression, filter, no interlace
+        ihdr.extend([8, 2, 0, 0, 0]);
+        write_chunk(&mut out, b"IHDR", &ihdr)?;
+        // every row starts with filter type 0 (none)
+        let mut raw = Vec::with_capacity(self.height * (self.width * 3 + 1));
+        for row in self.pixels.chunks(self.width.max(1)).take(self.height) {
+            raw.push(0);
+            raw.extend(row.concat());
+        }
+        write_chunk(&mut out, b"IDAT", &zlib_stored(&raw))?;
+        write_chunk(&mut out, b"IEND", &[])
+    }
+
+    // two pixels per character cell: the upper half block takes the
+    // foreground color of the upper pixel and the background of the lower
+    pub fn write_ansi<W: Write>(&self, mut out: W) -> io::Result<()> {
+        for y in (0..self.height).step_by(2) {
+            for x in 0..self.width {
+                let [r, g, b] = self.pixel(x, y);
+                let [br, bg, bb] = if y + 1 < self.height {
+                    self.pixel(x, y + 1)
+                } else {
+                    [0, 0, 0]
+                };
+                write!(
+                    out,
+                    "\x1b[38;2;{r};{g};{b}m\x1b[48;2;{br};{bg};{bb}m\u{2580}"
+                )?;
+            }
+            writeln!(out, "\x1b[0m")?;
+        }
+        Ok(())
+    }
+}
+
+// render the fractal, rows are shared out between the threads
+// one at a time so that expensive rows do not pile up on one thread
+pub fn render(fractal: &Fractal, options: &Options) -> Image {
+    let (width, height) = (options.width, options.height);
+    let mut pixels = vec![[0u8; 3]; width * height];
+    let threads = match options.threads {
+        0 => thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    };
+    if width > 0 {
+        let rows = Mutex::new(pixels.chunks_mut(width).enumerate());
+        thread::scope(|s| {
+            for _ in 0..threads.min(height) {
+                s.spawn(|| loop {
+                    // the lock is released before the row is rendered
+                    let next = rows.lock().expect("row lock poisoned").next();
+                    let Some((y, row)) = next else { break };
+                    for (x, pixel) in row.iter_mut().enumerate() {
+                        let point = options.viewport.point(x, y, width, height);
+                        let n = fractal.escape_time(point, options.max_iter, options.smooth);
+                        *pixel = color(n, options.max_iter);
+                    }
+                });
+            }
+        });
+    }
+    Image {
+        width,
+        height,
+        pixels,
+    }
+}
+
+// black inside the set, outside a smooth blue - white - orange gradient
+fn color(escape: Option<f64>, max_iter: u32) -> [u8; 3] {
+    let Some(n) = escape else {
+        return [0, 0, 0];
+    };
+    let t = (n / max_iter as f64).clamp(0.0, 1.0).sqrt();
+    let s = 1.0 - t;
+    let channel = |x: f64| (x.clamp(0.0, 1.0) * 255.0).round() as u8;
+    [
+        channel(9.0 * s * t * t * t),
+        channel(15.0 * s * s * t * t),
+        channel(8.5 * s * s * s * t),
+    ]
+}
+
+// length, type, data and CRC of the type and data
+fn write_chunk<W: Write>(out: &mut W, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    out.write_all(&(data.len() as u32).to_be_bytes())?;
+    out.write_all(kind)?;
+    out.write_all(data)?;
+    out.write_all(&crc32(&[kind, data]).to_be_bytes())
+}
+
+// zlib stream made of stored deflate blocks of at most 65535 bytes
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut z = vec![0x78, 0x01];
+    let mut blocks = data.chunks(0xffff).peekable();
+    if blocks.peek().is_none() {
+        // an empty stream still needs one final block
+        z.extend([1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        let len = block.len() as u16;
+        z.push(last as u8);
+        z.extend(len.to_le_bytes());
+        z.extend((!len).to_le_bytes());
+        z.extend(block);
+    }
+    z.extend(adler32(data).to_be_bytes());
+    z
+}
+
+fn crc32(parts: &[&[u8]]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in parts.iter().flat_map(|p| p.iter()) {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn small(fractal: Fractal, threads: usize) -> Image {
+        let options = Options {
+            width: 40,
+            height: 30,
+            viewport: match fractal {
+                Fractal::Mandelbrot => Viewport::mandelbrot(),
+                Fractal::Julia(_) => Viewport::julia(),
+            },
+            max_iter: 64,
+            smooth: true,
+            threads,
+        };
+        render(&fractal, &options)
+    }
+
+    // points inside the set never escape, points outside do
+    #[test]
+    fn test_escape_time() {
+        let m = Fractal::Mandelbrot;
+        assert_eq!(None, m.escape_time(Complex::new(0.0, 0.0), 100, true));
+        assert_eq!(None, m.escape_time(Complex::new(-1.0, 0.0), 100, true));
+        assert_eq!(
+            Some(1.0),
+            m.escape_time(Complex::new(300.0, 0.0), 100, false)
+        );
+        let slow = m.escape_time(Complex::new(0.3, 0.0), 1000, false).unwrap();
+        let fast = m.escape_time(Complex::new(1.0, 0.0), 1000, false).unwrap();
+        assert!(slow > fast);
+        // c = 0 gives the unit disk
+        let j = Fractal::Julia(Complex::new(0.0, 0.0));
+        assert_eq!(None, j.escape_time(Complex::new(0.6, 0.6), 100, true));
+        assert!(j.escape_time(Complex::new(0.8, 0.8), 100, true).is_some());
+    }
+
+    // the smooth count lies between the whole count and the next one
+    #[test]
+    fn test_smooth_escape_time() {
+        let m = Fractal::Mandelbrot;
+        for c in [
+            Complex::new(0.5, 0.5),
+            Complex::new(-0.75, 0.2),
+            Complex::new(0.26, 0.0),
+        ] {
+            let n = m.escape_time(c, 1000, false).unwrap();
+            let s = m.escape_time(c, 1000, true).unwrap();
+            assert!((n..=n + 1.0).contains(&s), "{c}: {n} vs {s}");
+        }
+    }
+
+    // pixel centers map symmetrically around the viewport center
+    #[test]
+    fn test_viewport() {
+        let v = Viewport {
+            center: Complex::new(1.0, 1.0),
+            width: 4.0,
+        };
+        assert_eq!(Complex::new(0.5, 1.5), v.point(1, 1, 4, 4));
+        assert_eq!(Complex::new(2.5, 0.5), v.point(3, 2, 4, 4));
+    }
+
+    // the result does not depend on the number of threads
+    #[test]
+    fn test_render_threads() {
+        let image = small(Fractal::Mandelbrot, 1);
+        assert_eq!(image, small(Fractal::Mandelbrot, 4));
+        assert_eq!(image, small(Fractal::Mandelbrot, 0));
+        // center of the main cardioid is inside, the corner is not
+        assert_eq!([0, 0, 0], image.pixel(26, 15));
+        assert_ne!([0, 0, 0], image.pixel(0, 0));
+        let julia = small(Fractal::Julia(Complex::new(-0.8, 0.156)), 3);
+        assert_eq!(julia, small(Fractal::Julia(Complex::new(-0.8, 0.156)), 1));
+    }
+
+    // PPM is a text header followed by the raw pixels
+    #[test]
+    fn test_ppm() {
+        let image = small(Fractal::Mandelbrot, 2);
+        let mut out = vec![];
+        image.write_ppm(&mut out).unwrap();
+        let header = b"P6\n40 30\n255\n";
+        assert_eq!(header, &out[..header.len()]);
+        assert_eq!(header.len() + 40 * 30 * 3, out.len());
+        assert_eq!(&image.pixel(1, 0), &out[header.len() + 3..header.len() + 6]);
+    }
+
+    // a PNG decoder reads back the same pixels
+    #[test]
+    fn test_png() {
+        let image = small(Fractal::Julia(Complex::new(0.285, 0.01)), 2);
+        let mut out = vec![];
+        image.write_png(&mut out).unwrap();
+        let decoder = png::Decoder::new(std::io::Cursor::new(out));
+        let mut reader = decoder.read_info().unwrap();
+        let mut buf = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buf).unwrap();
+        assert_eq!((40, 30), (info.width, info.height));
+        assert_eq!(png::ColorType::Rgb, info.color_type);
+        assert_eq!(image.pixels.concat(), buf[..info.buffer_size()]);
+    }
+
+    // known check values of the checksums
+    #[test]
+    fn test_checksums() {
+        assert_eq!(0xcbf4_3926, crc32(&[b"123456789"]));
+        assert_eq!(0xae42_6082, crc32(&[b"IEND"]));
+        assert_eq!(0x091e_01de, adler32(b"123456789"));
+        // large data is split into several stored blocks
+        let z = zlib_stored(&vec![7; 70_000]);
+        assert_eq!(2 + 5 + 65535 + 5 + 4465 + 4, z.len());
+    }
+
+    // one line of half blocks per two rows of pixels
+    #[test]
+    fn test_ansi() {
+        let image = small(Fractal::Mandelbrot, 1);
+        let mut out = vec![];
+        image.write_ansi(&mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(15, text.lines().count());
+        assert_eq!(40 * 15, text.matches('\u{2580}').count());
+        assert!(text.lines().all(|l| l.ends_with("\x1b[0m")));
+    }
+}
@@ -0,0 +1,388 @@
+// Gaussian integer number theory
+// A Complex number with signed integer parts a + bi is a Gaussian integer.
+// Gaussian integers form a Euclidean domain with the norm N(a+bi) = a^2 + b^2
+// so division with remainder, gcd and unique factorization all work
+// - div_rem: Euclidean division where N(remainder) <= N(divisor) / 2
+// - gcd and lcm, normalized to a single associate
+// - units (1, -1, i, -i) and associates
+// - Gaussian prime test and factorization into Gaussian primes
+//
+// Every number has four associates (multiples by a unit). The normalized
+// associate is the one in the first quadrant: real > 0 and imag >= 0.
+
+use crate::{Complex, SignedInt};
+
+impl<T: SignedInt> Complex<T> {
+    // Euclidean division, returns (quotient, remainder)
+    // so that self = quotient * rhs + remainder
+    // the quotient is self / rhs with both parts rounded to the nearest integer
+    // Note: panics when dividing by zero just like the / operator
+    pub fn div_rem(&self, rhs: &Self) -> (Self, Self) {
+        let numerator = self.copy() * rhs.conjugate();
+        let denominator = rhs.norm_sqr();
+        let q = Self::new(
+            div_round(numerator.real, denominator.clone()),
+            div_round(numerator.imag, denominator),
+        );
+        let r = self.copy() - q.copy() * rhs.copy();
+        (q, r)
+    }
+
+    // greatest common divisor using the Euclidean algorithm
+    // the result is normalized, gcd(0, 0) is 0
+    pub fn gcd(&self, other: &Self) -> Self {
+        let mut a = self.copy();
+        let mut b = other.copy();
+        while !b.is_zero() {
+            let (_, r) = a.div_rem(&b);
+            a = b;
+            b = r;
+        }
+        a.normalize()
+    }
+
+    // least common multiple, normalized
+    // lcm with 0 is 0
+    pub fn lcm(&self, other: &Self) -> Self {
+        if self.is_zero() || other.is_zero() {
+            return Self::new(T::zero(), T::zero());
+        }
+        let (q, _) = self.div_rem(&self.gcd(other));
+        (q * other.copy()).normalize()
+    }
+
+    // units are the numbers with norm 1: 1, -1, i and -i
+    pub fn is_unit(&self) -> bool {
+        self.norm_sqr() == T::one()
+    }
+
+    // all four associates: self, i * self, -self, -i * self
+    pub fn associates(&self) -> [Self; 4] {
+        let (a, b) = (self.real.clone(), self.imag.clone());
+        [
+            Self::new(a.clone(), b.clone()),
+            Self::new(-b.clone(), a.clone()),
+            Self::new(-a.clone(), -b.clone()),
+            Self::new(b, -a),
+        ]
+    }
+
+    // the associate in the first quadrant (real > 0, imag >= 0)
+    // 0 is its own only associate
+    pub fn normalize(&self) -> Self {
+        let zero = T::zero();
+        for n in self.associates() {
+            if n.real > zero && n.imag >= zero {
+                return n;
+            }
+        }
+        Self::new(zero.clone(), zero)
+    }
+
+    // a + bi is a Gaussian prime if either
+    // - both parts are non zero and the norm a^2 + b^2 is a prime
+    // - one part is zero and the other is ±p with p a prime and p = 3 mod 4
+    // Note: with both parts non zero the norm has to fit in T
+    pub fn is_gaussian_prime(&self) -> bool {
+        let zero = T::zero();
+        let (a, b) = (self.real.clone(), self.imag.clone());
+        if a != zero && b != zero {
+            return is_prime(&self.norm_sqr());
+        }
+        let p = if a == zero { b } else { a };
+        let two = T::one() + T::one();
+        let four = two.clone() + two.clone();
+        // an even p is never 3 mod 4, ruling it out first keeps abs
+        // away from T::MIN, which is even and has no positive value
+        if p.clone() % two == zero {
+            return false;
+        }
+        let p = abs(p);
+        is_prime(&p) && p % four.clone() == four - T::one()
+    }
+
+    // factorize into a unit and a list of normalized Gaussian primes
+    // so that unit * primes[0] * primes[1] * ... == self
+    // primes lying over the same rational prime are grouped together
+    // and the groups are ordered by that rational prime
+    // 0 has no factorization and returns None
+    // Note: the norm a^2 + b^2 has to fit in T
+    pub fn factorize(&self) -> Option<(Self, Vec<Self>)> {
+        if self.is_zero() {
+            return None;
+        }
+        let one = T::one();
+        let two = one.clone() + one.clone();
+        let three = two.clone() + one.clone();
+        let four = two.clone() + two.clone();
+        let mut rest = self.copy();
+        let mut primes = vec![];
+        // every Gaussian prime divides exactly one rational prime
+        // so factorize the norm over the integers first
+        // and then split each rational prime p into Gaussian primes
+        let mut n = self.norm_sqr();
+        let mut d = two.clone();
+        while n > one {
+            if d.clone() > n.clone() / d.clone() {
+                d = n.clone();
+            }
+            if n.clone() % d.clone() != T::zero() {
+                d = d + one.clone();
+                continue;
+            }
+            while n.clone() % d.clone() == T::zero() {
+                n = n / d.clone();
+            }
+            // candidates for Gaussian primes over p
+            // - 2 = -i(1+i)^2
+            // - p = 3 mod 4 stays prime
+            // - p = 1 mod 4 splits into (a+bi)(a-bi) with a^2 + b^2 = p
+            let candidates = if d == two {
+                vec![Self::new(one.clone(), one.clone())]
+            } else if d.clone() % four.clone() == three {
+                vec![Self::new(d.clone(), T::zero())]
+            } else {
+                let pi = sum_of_squares(&d);
+                vec![pi.normalize(), pi.conjugate().normalize()]
+            };
+            for pi in candidates {
+                loop {
+                    let (q, r) = rest.div_rem(&pi);
+                    if !r.is_zero() {
+                        break;
+                    }
+                    primes.push(pi.copy());
+                    rest = q;
+                }
+            }
+            d = d + one.clone();
+        }
+        Some((rest, primes))
+    }
+
+    fn is_zero(&self) -> bool {
+        self.real == T::zero() && self.imag == T::zero()
+    }
+}
+
+// round n / d to the nearest integer for d > 0, halves round up
+// from the truncated quotient and remainder, so that nothing larger
+// than n or d is computed and narrow types do not overflow
+fn div_round<T: SignedInt>(n: T, d: T) -> T {
+    let mut q = n.clone() / d.clone();
+    let mut r = n % d.clone();
+    // floor instead of truncate, then 0 <= r < d
+    if r < T::zero() {
+        q = q - T::one();
+        r = r + d.clone();
+    }
+    // r / d >= 1/2 written without 2r
+    if r.clone() >= d - r {
+        q + T::one()
+    } else {
+        q
+    }
+}
+
+fn abs<T: SignedInt>(n: T) -> T {
+    if n < T::zero() {
+        -n
+    } else {
+        n
+    }
+}
+
+// rational prime test by trial division
+fn is_prime<T: SignedInt>(n: &T) -> bool {
+    let two = T::one() + T::one();
+    if *n < two {
+        return false;
+    }
+    // d <= n / d instead of d^2 <= n, d^2 can overflow
+    let mut d = two;
+    while d.clone() <= n.clone() / d.clone() {
+        if n.clone() % d.clone() == T::zero() {
+            return false;
+        }
+        d = d + T::one();
+    }
+    true
+}
+
+// find a + bi with a^2 + b^2 = p for a prime p = 1 mod 4
+// such a representation always exists (Fermat's theorem)
+fn sum_of_squares<T: SignedInt>(p: &T) -> Complex<T> {
+    let mut a = T::one();
+    loop {
+        let rest = p.clone() - a.clone() * a.clone();
+        let b = isqrt(&rest);
+        if b.clone() * b.clone() == rest {
+            return Complex::new(a, b);
+        }
+        a = a + T::one();
+    }
+}
+
+// integer square root by binary search
+fn isqrt<T: SignedInt>(n: &T) -> T {
+    let two = T::one() + T::one();
+    let (mut lo, mut hi) = (T::zero(), n.clone());
+    while lo < hi {
+        let mid = hi.clone() - (hi.clone() - lo.clone()) / two.clone();
+        if mid <= n.clone() / mid.clone() {
+            lo = mid;
+        } else {
+            hi = mid - T::one();
+        }
+    }
+    lo
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BigInt;
+
+    // no intermediate value overflows for numbers near MAX
+    #[test]
+    fn test_narrow_types() {
+        assert!(Complex::<i8>::new(0, 127).is_gaussian_prime());
+        assert!(Complex::<i8>::new(-127, 0).is_gaussian_prime());
+        assert!(!Complex::<i8>::new(0, 125).is_gaussian_prime());
+        assert!(Complex::<i16>::new(32_719, 0).is_gaussian_prime());
+        assert!(!Complex::<i16>::new(0, i16::MAX).is_gaussian_prime());
+        assert!(!Complex::<i8>::new(i8::MIN, 0).is_gaussian_prime());
+        assert!(!Complex::<i8>::new(0, i8::MIN).is_gaussian_prime());
+        assert!(!Complex::<i16>::new(0, i16::MIN).is_gaussian_prime());
+        let (q, r) = Complex::new(127i8, -128).div_rem(&Complex::new(1, 0));
+        assert_eq!((Complex::new(127, -128), Complex::new(0, 0)), (q, r));
+        assert_eq!(-2i8, div_round(-5, 2));
+        assert_eq!(3i8, div_round(5, 2));
+        assert_eq!(-43i8, div_round(i8::MIN, 3));
+        assert_eq!(127i8, div_round(i8::MAX, 1));
+    }
+
+    // quotient is rounded so the remainder is small
+    #[test]
+    fn test_div_rem() {
+        let a = Complex::new(27, 23);
+        let b = Complex::new(8, 1);
+        let (q, r) = a.div_rem(&b);
+        assert_eq!(Complex::new(4, 2), q);
+        assert_eq!(Complex::new(-3, 3), r.copy());
+        assert!(2 * r.norm_sqr() <= b.norm_sqr());
+        assert_eq!(a, q * b + r);
+    }
+
+    // remainder is within half the norm for all signs
+    #[test]
+    fn test_div_rem_signs() {
+        for a in [(7, 3), (-7, 3), (7, -3), (-7, -3), (0, 5)] {
+            for b in [(2, 1), (-2, 1), (1, -3), (0, -2)] {
+                let a = Complex::new(a.0, a.1);
+                let b = Complex::new(b.0, b.1);
+                let (q, r) = a.div_rem(&b);
+                assert!(2 * r.norm_sqr() <= b.norm_sqr());
+                assert_eq!(a, q * b + r);
+            }
+        }
+    }
+
+    // 5 = (2+i)(2-i) and 3+i = (1+i)(2-i)
+    #[test]
+    fn test_gcd_lcm() {
+        let a = Complex::new(5, 0);
+        let b = Complex::new(3, 1);
+        assert_eq!(Complex::new(1, 2), a.gcd(&b));
+        assert_eq!(Complex::new(5, 5), a.lcm(&b));
+        assert_eq!(Complex::new(3, 1), Complex::new(0, 0).gcd(&b));
+        assert_eq!(Complex::new(0, 0), Complex::new(0, 0).lcm(&b));
+    }
+
+    // the four units
+    #[test]
+    fn test_is_unit() {
+        assert!(Complex::new(1, 0).is_unit());
+        assert!(Complex::new(-1, 0).is_unit());
+        assert!(Complex::new(0, 1).is_unit());
+        assert!(Complex::new(0, -1).is_unit());
+        assert!(!Complex::new(1, 1).is_unit());
+        assert!(!Complex::new(0, 0).is_unit());
+    }
+
+    // every associate normalizes to the same number
+    #[test]
+    fn test_normalize() {
+        let n = Complex::new(-3, 2);
+        for a in n.associates() {
+            assert_eq!(Complex::new(2, 3), a.normalize());
+        }
+        assert_eq!(Complex::new(5, 0), Complex::new(0, -5).normalize());
+    }
+
+    // Gaussian primes
+    #[test]
+    fn test_is_gaussian_prime() {
+        assert!(Complex::new(1, 1).is_gaussian_prime());
+        assert!(Complex::new(2, 1).is_gaussian_prime());
+        assert!(Complex::new(3, 0).is_gaussian_prime());
+        assert!(Complex::new(0, -7).is_gaussian_prime());
+        assert!(!Complex::new(2, 0).is_gaussian_prime());
+        assert!(!Complex::new(5, 0).is_gaussian_prime());
+        assert!(!Complex::new(1, 0).is_gaussian_prime());
+        assert!(!Complex::new(0, 0).is_gaussian_prime());
+        assert!(!Complex::new(3, 1).is_gaussian_prime());
+    }
+
+    // 10 = -(1+i)^2 (1+2i) (2+i)
+    #[test]
+    fn test_factorize() {
+        let (unit, primes) = Complex::new(10, 0).factorize().unwrap();
+        assert_eq!(Complex::new(-1, 0), unit);
+        assert_eq!(
+            vec![
+                Complex::new(1, 1),
+                Complex::new(1, 1),
+                Complex::new(1, 2),
+                Complex::new(2, 1)
+            ],
+            primes
+        );
+        assert_eq!(None, Complex::new(0, 0).factorize());
+    }
+
+    // product of the factors gives back the number
+    #[test]
+    fn test_factorize_product() {
+        for n in [(1, 0), (0, -1), (7, 0), (12, -30), (-17, 19), (100, 100)] {
+            let n = Complex::new(n.0, n.1);
+            let (unit, primes) = n.factorize().unwrap();
+            assert!(unit.is_unit());
+            let mut product = unit;
+            for p in primes {
+                assert!(p.is_gaussian_prime());
+                product *= p;
+            }
+            assert_eq!(n, product);
+        }
+    }
+
+    // Complex<BigInt> far beyond the range of i128
+    #[test]
+    fn test_bigint() {
+        let big = |a: i64, b: i64| Complex::new(BigInt::from(a), BigInt::from(b));
+        let pow = |z: &Complex<BigInt>, n: usize| (0..n).fold(big(1, 0), |acc, _| acc * z.clone());
+        let a = pow(&big(2, 1), 40) * pow(&big(3, 2), 20);
+        let b = pow(&big(2, 1), 10) * pow(&big(1, 1), 30);
+        assert!(a.norm_sqr() > BigInt::from(i128::MAX));
+        assert_eq!(pow(&big(2, 1), 10).normalize(), a.gcd(&b));
+        let (unit, primes) = a.factorize().unwrap();
+        assert!(unit.is_unit());
+        assert_eq!(60, primes.len());
+        assert!(primes[..40].iter().all(|p| *p == big(2, 1)));
+        assert!(primes[40..].iter().all(|p| *p == big(3, 2)));
+        // 10^9 + 7 = 3 mod 4 stays prime, 10^9 + 9 = 1 mod 4 splits
+        assert!(big(1_000_000_007, 0).is_gaussian_prime());
+        assert!(!big(1_000_000_009, 0).is_gaussian_prime());
+    }
+}
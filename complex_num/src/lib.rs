@@ -0,0 +1,495 @@
+// In this code sample we will implement complex number.
+// Complex number is of the form a + bi
+// where a is the real part and bi is the imaginary part
+// features to be implemented
+// - instantiate/create complex numbers
+// - display value of complex numbers
+// - basic arithmetic operations on complex numbers
+// using +, -, *, / operators
+// - negation, remainder, assignment operators, operators on references
+// and mixed operators with a real scalar (z * 3, 2 + z)
+// - real and imaginary parts can be any numeric scalar
+// i.e. Complex<i64> for exact integer math or Complex<f64> for signal code
+// - polar form and transcendental functions for floating point parts
+// - checked, wrapping and saturating arithmetic for integer parts
+// - Gaussian integer number theory for signed integer parts
+// - parse complex numbers from strings
+// - display honoring width, precision, sign and alternate (polar) flags
+// - fast Fourier transform in the fft module
+// - polynomials with complex coefficients in the poly module
+// - dense matrices, LU decomposition and eigenvalues in the matrix module
+// - serde support behind the serde feature and zero-copy views of
+// Complex slices as interleaved re, im scalars
+// - Mandelbrot and Julia set rendering in the fractal module
+// - add, mul, conj, norm and dot over whole slices in the batch module
+// with portable SIMD kernels behind the (nightly only) simd feature
+// - arbitrary precision BigInt and exact Rational scalars
+// i.e. Complex<Rational> divides exactly, Complex<BigInt> never overflows
+// - Quaternion numbers for 3D rotations and Dual numbers for
+// forward mode automatic differentiation
+// - approximate and ULP based equality of floating point numbers
+// and the assert_complex_eq! macro for tests
+
+#![cfg_attr(feature = "simd", feature(portable_simd))]
+
+use std::ops::{Add, Div, Mul, Sub};
+
+// public only so that assert_complex_eq! can reach its helpers
+#[doc(hidden)]
+pub mod approx;
+pub mod batch;
+mod bigint;
+mod cast;
+mod checked;
+mod dual;
+mod error;
+pub mod fft;
+mod float;
+mod fmt;
+pub mod fractal;
+mod gaussian;
+pub mod matrix;
+mod num;
+mod ops;
+mod parse;
+pub mod poly;
+mod quaternion;
+mod rational;
+#[cfg(feature = "serde")]
+pub mod serialize;
+
+pub use bigint::BigInt;
+pub use dual::Dual;
+pub use error::{ComplexError, ParseComplexError, ParseErrorKind, ParseNumberError};
+pub use num::{Float, Int, Num, SignedInt};
+pub use quaternion::Quaternion;
+pub use rational::Rational;
+
+// Define Complex struct with two fields
+// one for real part and other for the imaginary part
+// both parts have the same scalar type T
+// Complex is Copy whenever the scalar is, so the operators
+// can take their operands by value without moving them away
+// repr(C) guarantees the layout of [T; 2], real part first,
+// so slices can be shared with C code and raw I/Q buffers (see cast.rs)
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct Complex<T> {
+    pub real: T,
+    pub imag: T,
+}
+
+// implement new function
+// so that new Complex number can be created
+impl<T: Num> Complex<T> {
+    pub fn new(real: T, imag: T) -> Self {
+        Self { real, imag }
+    }
+
+    // conjugate of any complex number is the number
+    // where the sign of the imaginary part is reversed
+    // 7 + 4i will have conjugate 7 - 4i
+    // Note: for unsigned scalars only numbers with zero imaginary
+    // part have a representable conjugate, others overflow
+    // the same way as `0u32 - 1` does
+    pub fn conjugate(&self) -> Self {
+        Self {
+            real: self.real.clone(),
+            imag: T::zero() - self.imag.clone(),
+        }
+    }
+
+    // square of the modulus a^2 + b^2
+    // unlike norm it stays exact for integer parts
+    pub fn norm_sqr(&self) -> T {
+        let (a, b) = (self.real.clone(), self.imag.clone());
+        a.clone() * a + b.clone() * b
+    }
+
+    // copy the number into a new Complex
+    // same as the derived Clone, kept for existing callers
+    pub fn copy(&self) -> Self {
+        self.clone()
+    }
+}
+
+// implement Add trait so that addition operation can be done on Complex
+impl<T: Num> Add for Complex<T> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        // (a+bi) + (x+yi) = (a+x)+(b+y)i
+        Self {
+            real: self.real + rhs.real,
+            imag: self.imag + rhs.imag,
+        }
+    }
+}
+
+// implement Sub trait so that subtraction operation can be done on Complex
+impl<T: Num> Sub for Complex<T> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        // (a+bi) - (x+yi) = (a-x)+(b-y)i
+        Self {
+            real: self.real - rhs.real,
+            imag: self.imag - rhs.imag,
+        }
+    }
+}
+
+// implement Mul trait so that multiplication operation can be done on Complex
+impl<T: Num> Mul for Complex<T> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self::Output {
+        // (a+bi)(x+yi) = ax + ayi + bxi - by = (ax-by) + (ay+ bx)i
+        let (a, b, x, y) = (self.real, self.imag, rhs.real, rhs.imag);
+        let real = a.clone() * x.clone() - b.clone() * y.clone();
+        let imag = a * y + b * x;
+        Self { real, imag }
+    }
+}
+
+// implement Div trait so that division operation can be done on Complex
+impl<T: Num> Div for Complex<T> {
+    type Output = Self;
+    fn div(self, rhs: Self) -> Self::Output {
+        // (a+bi)/(x+yi)
+        // = ((a+bi)(x-yi))/((x+yi)(x-yi))
+        // = ax-ayi+bxi+by/(x^2+y^2)
+        let c = rhs.conjugate();
+        let denominator = rhs.norm_sqr();
+        let numerator = self * c;
+        // Note: for integer scalars division will always round off the
+        // result to whole number for both real and imaginary part
+        // use Complex<f32> or Complex<f64> for more accurate result
+        Self {
+            real: numerator.real / denominator.clone(),
+            imag: numerator.imag / denominator,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // test Complex number display using to_string
+    // both real and imaginary parts are positive
+    #[test]
+    fn test_display_positive_num() {
+        let num = Complex::new(2, 3);
+        let s = num.to_string();
+        assert_eq!("2 + 3i".to_string(), s);
+    }
+    // real part is negative but imaginary part is positive
+    #[test]
+    fn test_display_negative_real() {
+        let num = Complex::new(-2, 3);
+        let s = num.to_string();
+        assert_eq!("-2 + 3i".to_string(), s);
+    }
+    // real part is positive but imaginary part is negative
+    #[test]
+    fn test_display_negative_imag() {
+        let num = Complex::new(2, -3);
+        let s = num.to_string();
+        assert_eq!("2 - 3i".to_string(), s);
+    }
+    // both real and imaginary parts are negative
+    #[test]
+    fn test_display_negative_num() {
+        let num = Complex::new(-2, -3);
+        let s = num.to_string();
+        assert_eq!("-2 - 3i".to_string(), s);
+    }
+    // real part is non zero and imaginary part is zero
+    #[test]
+    fn test_display_imag_zero() {
+        let num = Complex::new(2, 0);
+        let s = num.to_string();
+        assert_eq!("2".to_string(), s);
+    }
+    // real part is zero and imaginary part is non zero
+    #[test]
+    fn test_display_real_zero() {
+        let num = Complex::new(0, 3);
+        let s = num.to_string();
+        assert_eq!("3i".to_string(), s);
+    }
+    // real part is zero and imaginary part is non zero and negative
+    #[test]
+    fn test_display_real_zero_neg() {
+        let num = Complex::new(0, -3);
+        let s = num.to_string();
+        assert_eq!("-3i".to_string(), s);
+    }
+    // both real part and imaginary parts are zero
+    #[test]
+    fn test_display_zero() {
+        let num = Complex::new(0, 0);
+        let s = num.to_string();
+        assert_eq!("0".to_string(), s);
+    }
+
+    // add two positive numbers
+    #[test]
+    fn test_add_positives() {
+        let lhs = Complex::new(2, 5);
+        let rhs = Complex::new(4, 7);
+        let expected = Complex::new(6, 12);
+        let result = lhs + rhs;
+        assert_eq!(expected, result);
+    }
+
+    // add two lhs = a+bi, rhs = x-yi
+    #[test]
+    fn test_add_pos_neg1() {
+        let lhs = Complex::new(2, 5);
+        let rhs = Complex::new(4, -7);
+        let expected = Complex::new(6, -2);
+        let result = lhs + rhs;
+        assert_eq!(expected, result);
+    }
+
+    // add two lhs = a+bi, rhs = -x+yi
+    #[test]
+    fn test_add_pos_neg2() {
+        let lhs = Complex::new(2, 5);
+        let rhs = Complex::new(-4, 7);
+        let expected = Complex::new(-2, 12);
+        let result = lhs + rhs;
+        assert_eq!(expected, result);
+    }
+
+    // add two lhs = a+bi, rhs = -x-yi
+    #[test]
+    fn test_add_pos_neg3() {
+        let lhs = Complex::new(2, 5);
+        let rhs = Complex::new(-4, -7);
+        let expected = Complex::new(-2, -2);
+        let result = lhs + rhs;
+        assert_eq!(expected, result);
+    }
+
+    // add two lhs = a-bi, rhs = x+yi
+    #[test]
+    fn test_add_neg_pos1() {
+        let lhs = Complex::new(2, -5);
+        let rhs = Complex::new(4, 7);
+        let expected = Complex::new(6, 2);
+        let result = lhs + rhs;
+        assert_eq!(expected, result);
+    }
+
+    // add two lhs = -a+bi, rhs = x+yi
+    #[test]
+    fn test_add_neg_pos2() {
+        let lhs = Complex::new(-2, 5);
+        let rhs = Complex::new(4, 7);
+        let expected = Complex::new(2, 12);
+        let result = lhs + rhs;
+        assert_eq!(expected, result);
+    }
+
+    // add two lhs = -a-bi, rhs = x+yi
+    #[test]
+    fn test_add_neg_pos3() {
+        let lhs = Complex::new(-2, -5);
+        let rhs = Complex::new(4, 7);
+        let expected = Complex::new(2, 2);
+        let result = lhs + rhs;
+        assert_eq!(expected, result);
+    }
+
+    // add two lhs = a-bi, rhs = x-yi
+    #[test]
+    fn test_add_neg_neg1() {
+        let lhs = Complex::new(2, -5);
+        let rhs = Complex::new(4, -7);
+        let expected = Complex::new(6, -12);
+        let result = lhs + rhs;
+        assert_eq!(expected, result);
+    }
+
+    // add two lhs = -a-bi, rhs = -x-yi
+    #[test]
+    fn test_add_neg_neg2() {
+        let lhs = Complex::new(-2, -5);
+        let rhs = Complex::new(-4, -7);
+        let expected = Complex::new(-6, -12);
+        let result = lhs + rhs;
+        assert_eq!(expected, result);
+    }
+
+    // add conjugate
+    #[test]
+    fn test_add_conjugate() {
+        let n = Complex::new(-2, -5);
+        let expected = Complex::new(n.real * 2, 0);
+        let result = n.conjugate() + n;
+        assert_eq!(expected, result);
+    }
+
+    // sub same number
+    #[test]
+    fn test_sub_same() {
+        let n = Complex::new(-2, -5);
+        let n1 = Complex::new(n.real, n.imag);
+        let expected = Complex::new(n.real * 2, n.imag * 2);
+        let result = n1 + n;
+        assert_eq!(expected, result);
+    }
+
+    // sub conjugate
+    #[test]
+    fn test_sub_conjugate() {
+        let n = Complex::new(-2, -5);
+        let c = n.conjugate();
+        let expected = Complex::new(0, n.imag * 2);
+        let result = n - c;
+        assert_eq!(expected, result);
+    }
+
+    // sub (a+bi) - (x+yi)
+    #[test]
+    fn test_sub_pos1() {
+        let lhs = Complex::new(2, 5);
+        let rhs = Complex::new(4, 7);
+        let expected = Complex::new(-2, -2);
+        let result = lhs - rhs;
+        assert_eq!(expected, result);
+    }
+
+    // sub (a+bi) - (x+bi)
+    #[test]
+    fn test_sub_same_imag() {
+        let lhs = Complex::new(2, 5);
+        let rhs = Complex::new(4, 5);
+        let expected = Complex::new(-2, 0);
+        let result = lhs - rhs;
+        assert_eq!(expected, result);
+    }
+
+    // sub (a+bi) - (x+bi)
+    #[test]
+    fn test_sub_same_real() {
+        let lhs = Complex::new(2, 5);
+        let rhs = Complex::new(2, 7);
+        let expected = Complex::new(0, -2);
+        let result = lhs - rhs;
+        assert_eq!(expected, result);
+    }
+
+    // mul (a+bi) * (x+yi)
+    #[test]
+    fn test_mul_pos() {
+        let lhs = Complex::new(2, 5);
+        let rhs = Complex::new(4, 7);
+        let expected = Complex::new(-27, 34);
+        let result = lhs * rhs;
+        assert_eq!(expected, result);
+    }
+
+    // mul (a+bi) * 0
+    #[test]
+    fn test_mul_zero() {
+        let lhs = Complex::new(2, 5);
+        let rhs = Complex::new(0, 0);
+        let expected = Complex::new(0, 0);
+        let result = lhs * rhs;
+        assert_eq!(expected, result);
+    }
+
+    // multiply with conjugate
+    #[test]
+    fn test_mul_conjugate() {
+        let n = Complex::new(2, 5);
+        let expected = Complex::new(n.real * n.real + n.imag * n.imag, 0);
+        let result = n.conjugate() * n;
+        assert_eq!(expected, result);
+    }
+
+    // divide by same
+    #[test]
+    fn test_div_same() {
+        let lhs = Complex::new(2, 5);
+        let rhs = Complex::new(lhs.real, lhs.imag);
+        let expected = Complex::new(1, 0);
+        let result = lhs / rhs;
+        assert_eq!(expected, result);
+    }
+
+    // divide by imag zero
+    #[test]
+    fn test_div_pos() {
+        let lhs = Complex::new(20, 25);
+        let rhs = Complex::new(5, 0);
+        let expected = Complex::new(4, 5);
+        let result = lhs / rhs;
+        assert_eq!(expected, result);
+    }
+
+    // divide by real zero
+    #[test]
+    fn test_div_real_zero() {
+        let lhs = Complex::new(20, 25);
+        let rhs = Complex::new(0, 5);
+        let expected = Complex::new(5, -4);
+        let result = lhs / rhs;
+        assert_eq!(expected, result);
+    }
+
+    // divide by zero
+    #[test]
+    #[should_panic]
+    fn test_div_zero() {
+        let lhs = Complex::new(2, 5);
+        let rhs = Complex::new(0, 0);
+        let _ = lhs / rhs;
+    }
+
+    // float parts are displayed as they are
+    #[test]
+    fn test_display_f64() {
+        let num = Complex::new(2.5, -0.5);
+        assert_eq!("2.5 - 0.5i".to_string(), num.to_string());
+        let num = Complex::new(0.0, 1.5);
+        assert_eq!("1.5i".to_string(), num.to_string());
+    }
+
+    // float division does not round off
+    #[test]
+    fn test_div_f64() {
+        let lhs = Complex::new(1.0, 0.0);
+        let rhs = Complex::new(0.0, 2.0);
+        let expected = Complex::new(0.0, -0.5);
+        let result = lhs / rhs;
+        assert_complex_eq!(expected, result, ulps = 0);
+        // 1/3 is rounded, but to the closest float
+        let third = Complex::new(1.0, 1.0) / Complex::new(3.0, 3.0);
+        assert_complex_eq!(Complex::new(1.0 / 3.0, 0.0), third, ulps = 1);
+    }
+
+    // i64 parts can hold values beyond i32
+    #[test]
+    fn test_mul_i64() {
+        let lhs = Complex::new(3_000_000_000i64, 0);
+        let rhs = Complex::new(0, 2);
+        let expected = Complex::new(0, 6_000_000_000i64);
+        let result = lhs * rhs;
+        assert_eq!(expected, result);
+    }
+
+    // unsigned parts work as long as nothing goes below zero
+    #[test]
+    fn test_unsigned() {
+        let lhs = Complex::new(6u8, 4);
+        let rhs = Complex::new(2u8, 0);
+        assert_eq!(Complex::new(8u8, 4), lhs.copy() + rhs.copy());
+        assert_eq!(Complex::new(3u8, 2), lhs / rhs);
+        assert_eq!(
+            "2".to_string(),
+            Complex::new(2u8, 0).conjugate().to_string()
+        );
+    }
+}
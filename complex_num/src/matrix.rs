@@ -0,0 +1,658 @@
+// Dense matrices of Complex numbers
+// small enough problems (quantum states, circuits) do not need
+// an external linear algebra crate
+// - element access with m[(row, col)]
+// - +, -, matrix * matrix and matrix * vector
+// - transpose and conjugate (Hermitian) transpose
+// - LU decomposition with partial pivoting, giving determinant,
+//   linear solve and inverse
+// - eigenvalues of Hermitian matrices with the Jacobi method
+// dimension mismatches are bugs in the caller and panic,
+// a singular matrix, up to rounding, is a property of the data and
+// gives None
+
+use crate::{Complex, Float, Num};
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::ops::{Add, Index, IndexMut, Mul, Sub};
+
+// elements are stored row by row
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComplexMatrix<T> {
+    rows: usize,
+    cols: usize,
+    data: Vec<Complex<T>>,
+}
+
+impl<T: Num> ComplexMatrix<T> {
+    // rows x cols matrix of zeros
+    pub fn zeros(rows: usize, cols: usize) -> Self {
+        let zero = Complex::new(T::zero(), T::zero());
+        Self {
+            rows,
+            cols,
+            data: vec![zero; rows * cols],
+        }
+    }
+
+    // n x n identity matrix
+    pub fn identity(n: usize) -> Self {
+        let mut m = Self::zeros(n, n);
+        for i in 0..n {
+            m[(i, i)] = Complex::new(T::one(), T::zero());
+        }
+        m
+    }
+
+    // build from a list of rows
+    // Note: panics if the rows have different lengths
+    pub fn from_rows(rows: Vec<Vec<Complex<T>>>) -> Self {
+        let cols = rows.first().map_or(0, |r| r.len());
+        assert!(
+            rows.iter().all(|r| r.len() == cols),
+            "rows have different lengths"
+        );
+        Self {
+            rows: rows.len(),
+            cols,
+            data: rows.into_iter().flatten().collect(),
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn is_square(&self) -> bool {
+        self.rows == self.cols
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut t = Self::zeros(self.cols, self.rows);
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                t[(j, i)] = self[(i, j)].clone();
+            }
+        }
+        t
+    }
+
+    // transpose and conjugate every element, A^H
+    pub fn conjugate_transpose(&self) -> Self {
+        let mut t = Self::zeros(self.cols, self.rows);
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                t[(j, i)] = self[(i, j)].conjugate();
+            }
+        }
+        t
+    }
+
+    // matrix times column vector
+    // Note: panics if the vector length is not the number of columns
+    pub fn mul_vec(&self, v: &[Complex<T>]) -> Vec<Complex<T>> {
+        assert_eq!(self.cols, v.len(), "dimension mismatch");
+        (0..self.rows)
+            .map(|i| {
+                (0..self.cols)
+                    .map(|j| self[(i, j)].clone() * v[j].clone())
+                    .sum()
+            })
+            .collect()
+    }
+}
+
+impl<T: Float> ComplexMatrix<T> {
+    // LU decomposition with partial pivoting, P A = L U
+    // Note: panics if the matrix is not square
+    pub fn lu(&self) -> Lu<T> {
+        assert!(self.is_square(), "LU decomposition needs a square matrix");
+        let n = self.rows;
+        let mut lu = self.clone();
+        let mut perm: Vec<usize> = (0..n).collect();
+        let mut swaps = 0;
+        let mut singular = false;
+        // rounding leaves pivots of a singular matrix at about epsilon
+        // times its entries instead of exactly zero
+        let largest = self.data.iter().fold(T::zero(), |m, z| {
+            let r = z.norm();
+            if r > m {
+                r
+            } else {
+                m
+            }
+        });
+        let tolerance = T::from_f64(n as f64) * T::epsilon() * largest;
+        for k in 0..n {
+            // the largest element of the column as pivot keeps it stable
+            let p = (k..n)
+                .max_by(|&a, &b| {
+                    let (x, y) = (lu[(a, k)].norm_sqr(), lu[(b, k)].norm_sqr());
+                    x.partial_cmp(&y).unwrap_or(std::cmp::Ordering::Equal)
+                })
+                .unwrap_or(k);
+            if lu[(p, k)].norm() <= tolerance {
+                singular = true;
+                continue;
+            }
+            if p != k {
+                for j in 0..n {
+                    lu.data.swap(k * n + j, p * n + j);
+                }
+                perm.swap(k, p);
+                swaps += 1;
+            }
+            let pivot = lu[(k, k)];
+            for i in k + 1..n {
+                let factor = lu[(i, k)] / pivot;
+                lu[(i, k)] = factor;
+                for j in k + 1..n {
+                    let u = lu[(k, j)];
+                    lu[(i, j)] -= factor * u;
+                }
+            }
+        }
+        Lu {
+            lu,
+            perm,
+            odd_swaps: swaps % 2 == 1,
+            singular,
+        }
+    }
+
+    pub fn determinant(&self) -> Complex<T> {
+        self.lu().determinant()
+    }
+
+    // solve A x = b, None if A is singular
+    pub fn solve(&self, b: &[Complex<T>]) -> Option<Vec<Complex<T>>> {
+        self.lu().solve(b)
+    }
+
+    // inverse matrix, None if A is singular
+    pub fn inverse(&self) -> Option<Self> {
+        self.lu().inverse()
+    }
+
+    // A == A^H up to the tolerance
+    pub fn is_hermitian(&self, tolerance: T) -> bool {
+        self.is_square()
+            && (0..self.rows).all(|i| {
+                (0..self.cols)
+                    .all(|j| (self[(i, j)] - self[(j, i)].conjugate()).norm() <= tolerance)
+            })
+    }
+
+    // eigenvalues of a Hermitian matrix in ascending order
+    // cyclic Jacobi method: every off diagonal element a[p][q] in turn is
+    // zeroed with a unitary rotation J, A <- J^H A J, until A is diagonal
+    // Note: panics if the matrix is not square, the strictly upper
+    // triangle is assumed to be the conjugate of the lower one
+    pub fn hermitian_eigenvalues(&self) -> Vec<T> {
+        assert!(self.is_square(), "eigenvalues need a square matrix");
+        let n = self.rows;
+        let mut a = self.clone();
+        let zero = Complex::new(T::zero(), T::zero());
+        let two = T::one() + T::one();
+        for _ in 0..100 {
+            let off: T = (0..n)
+                .flat_map(|i| (0..n).filter(move |&j| j != i).map(move |j| (i, j)))
+                .fold(T::zero(), |s, (i, j)| s + a[(i, j)].norm_sqr());
+            let diag: T = (0..n).fold(T::zero(), |s, i| s + a[(i, i)].norm_sqr());
+            if off <= T::epsilon() * T::epsilon() * diag || off == T::zero() {
+                break;
+            }
+            for p in 0..n {
+                for q in p + 1..n {
+                    let b = a[(p, q)];
+                    let r = b.norm();
+                    if r == T::zero() {
+                        continue;
+                    }
+                    // phase e^(-i phi) of b turns the 2x2 block real
+                    // then a real rotation (c, s) zeroes it
+                    let phase = Complex::new(b.real / r, -b.imag / r);
+                    let (app, aqq) = (a[(p, p)].real, a[(q, q)].real);
+                    let tau = (aqq - app) / (two * r);
+                    let t = if tau >= T::zero() {
+                        T::one() / (tau + (T::one() + tau * tau).sqrt())
+                    } else {
+                        -T::one() / (-tau + (T::one() + tau * tau).sqrt())
+                    };
+                    let c = T::one() / (T::one() + t * t).sqrt();
+                    let s = t * c;
+                    // J restricted to rows/cols p, q
+                    let jpp = Complex::new(c, T::zero());
+                    let jpq = Complex::new(s, T::zero());
+                    let jqp = phase * -s;
+                    let jqq = phase * c;
+                    // A <- A J
+                    for k in 0..n {
+                        let (akp, akq) = (a[(k, p)], a[(k, q)]);
+                        a[(k, p)] = akp * jpp + akq * jqp;
+                        a[(k, q)] = akp * jpq + akq * jqq;
+                    }
+                    // A <- J^H A
+                    for k in 0..n {
+                        let (apk, aqk) = (a[(p, k)], a[(q, k)]);
+                        a[(p, k)] = jpp.conjugate() * apk + jqp.conjugate() * aqk;
+                        a[(q, k)] = jpq.conjugate() * apk + jqq.conjugate() * aqk;
+                    }
+                    a[(p, q)] = zero;
+                    a[(q, p)] = zero;
+                }
+            }
+        }
+        let mut values: Vec<T> = (0..n).map(|i| a[(i, i)].real).collect();
+        values.sort_by(|x, y| x.partial_cmp(y).unwrap_or(std::cmp::Ordering::Equal));
+        values
+    }
+}
+
+// Result of an LU decomposition, P A = L U
+// L (unit lower triangular) and U (upper triangular)
+// are stored together in one matrix
+#[derive(Debug, Clone)]
+pub struct Lu<T> {
+    lu: ComplexMatrix<T>,
+    perm: Vec<usize>,
+    odd_swaps: bool,
+    singular: bool,
+}
+
+impl<T: Float> Lu<T> {
+    pub fn is_singular(&self) -> bool {
+        self.singular
+    }
+
+    // unit lower triangular factor
+    pub fn l(&self) -> ComplexMatrix<T> {
+        let n = self.lu.rows;
+        let mut l = ComplexMatrix::identity(n);
+        for i in 0..n {
+            for j in 0..i {
+                l[(i, j)] = self.lu[(i, j)];
+            }
+        }
+        l
+    }
+
+    // upper triangular factor
+    pub fn u(&self) -> ComplexMatrix<T> {
+        let n = self.lu.rows;
+        let mut u = ComplexMatrix::zeros(n, n);
+        for i in 0..n {
+            for j in i..n {
+                u[(i, j)] = self.lu[(i, j)];
+            }
+        }
+        u
+    }
+
+    // row i of P A is row permutation()[i] of A
+    pub fn permutation(&self) -> &[usize] {
+        &self.perm
+    }
+
+    // product of the diagonal of U, negated for an odd permutation
+    pub fn determinant(&self) -> Complex<T> {
+        if self.singular {
+            return Complex::new(T::zero(), T::zero());
+        }
+        let det: Complex<T> = (0..self.lu.rows).map(|i| self.lu[(i, i)]).product();
+        if self.odd_swaps {
+            Complex::new(T::zero(), T::zero()) - det
+        } else {
+            det
+        }
+    }
+
+    // forward substitution with L, then back substitution with U
+    // Note: panics if b does not match the matrix size
+    pub fn solve(&self, b: &[Complex<T>]) -> Option<Vec<Complex<T>>> {
+        let n = self.lu.rows;
+        assert_eq!(n, b.len(), "dimension mismatch");
+        if self.singular {
+            return None;
+        }
+        let mut x: Vec<Complex<T>> = self.perm.iter().map(|&i| b[i]).collect();
+        for i in 0..n {
+            for j in 0..i {
+                let l = self.lu[(i, j)];
+                let xj = x[j];
+                x[i] -= l * xj;
+            }
+        }
+        for i in (0..n).rev() {
+            for j in i + 1..n {
+                let u = self.lu[(i, j)];
+                let xj = x[j];
+                x[i] -= u * xj;
+            }
+            x[i] /= self.lu[(i, i)];
+        }
+        Some(x)
+    }
+
+    // solve for every column of the identity
+    pub fn inverse(&self) -> Option<ComplexMatrix<T>> {
+        let n = self.lu.rows;
+        let mut inv = ComplexMatrix::zeros(n, n);
+        let zero = Complex::new(T::zero(), T::zero());
+        for j in 0..n {
+            let mut e = vec![zero; n];
+            e[j] = Complex::new(T::one(), T::zero());
+            let col = self.solve(&e)?;
+            for (i, x) in col.into_iter().enumerate() {
+                inv[(i, j)] = x;
+            }
+        }
+        Some(inv)
+    }
+}
+
+// m[(row, col)]
+impl<T> Index<(usize, usize)> for ComplexMatrix<T> {
+    type Output = Complex<T>;
+    fn index(&self, (i, j): (usize, usize)) -> &Self::Output {
+        assert!(i < self.rows && j < self.cols, "index out of bounds");
+        &self.data[i * self.cols + j]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for ComplexMatrix<T> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut Self::Output {
+        assert!(i < self.rows && j < self.cols, "index out of bounds");
+        &mut self.data[i * self.cols + j]
+    }
+}
+
+// element wise addition
+impl<T: Num> Add for &ComplexMatrix<T> {
+    type Output = ComplexMatrix<T>;
+    fn add(self, rhs: Self) -> Self::Output {
+        assert_eq!(
+            (self.rows, self.cols),
+            (rhs.rows, rhs.cols),
+            "dimension mismatch"
+        );
+        ComplexMatrix {
+            rows: self.rows,
+            cols: self.cols,
+            data: self
+                .data
+                .iter()
+                .zip(&rhs.data)
+                .map(|(a, b)| a + b)
+                .collect(),
+        }
+    }
+}
+
+impl<T: Num> Add for ComplexMatrix<T> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        &self + &rhs
+    }
+}
+
+// element wise subtraction
+impl<T: Num> Sub for &ComplexMatrix<T> {
+    type Output = ComplexMatrix<T>;
+    fn sub(self, rhs: Self) -> Self::Output {
+        assert_eq!(
+            (self.rows, self.cols),
+            (rhs.rows, rhs.cols),
+            "dimension mismatch"
+        );
+        ComplexMatrix {
+            rows: self.rows,
+            cols: self.cols,
+            data: self
+                .data
+                .iter()
+                .zip(&rhs.data)
+                .map(|(a, b)| a - b)
+                .collect(),
+        }
+    }
+}
+
+impl<T: Num> Sub for ComplexMatrix<T> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        &self - &rhs
+    }
+}
+
+// matrix product, (AB)[i][j] = sum A[i][k] B[k][j]
+impl<T: Num> Mul for &ComplexMatrix<T> {
+    type Output = ComplexMatrix<T>;
+    fn mul(self, rhs: Self) -> Self::Output {
+        assert_eq!(self.cols, rhs.rows, "dimension mismatch");
+        let mut m = ComplexMatrix::zeros(self.rows, rhs.cols);
+        for i in 0..self.rows {
+            for k in 0..self.cols {
+                let a = &self[(i, k)];
+                for j in 0..rhs.cols {
+                    m[(i, j)] += a.clone() * rhs[(k, j)].clone();
+                }
+            }
+        }
+        m
+    }
+}
+
+impl<T: Num> Mul for ComplexMatrix<T> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self::Output {
+        &self * &rhs
+    }
+}
+
+// one row per line, elements separated by tabs
+impl<T: Num> Display for ComplexMatrix<T> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        for i in 0..self.rows {
+            let row: Vec<String> = (0..self.cols).map(|j| self[(i, j)].to_string()).collect();
+            writeln!(f, "{}", row.join("\t"))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn c(real: f64, imag: f64) -> Complex<f64> {
+        Complex::new(real, imag)
+    }
+
+    fn assert_close(expected: &ComplexMatrix<f64>, result: &ComplexMatrix<f64>) {
+        assert_eq!(
+            (expected.rows(), expected.cols()),
+            (result.rows(), result.cols())
+        );
+        for (e, r) in expected.data.iter().zip(&result.data) {
+            assert!((*e - *r).norm() < 1e-9, "expected\n{expected}got\n{result}");
+        }
+    }
+
+    // Pauli matrices, X Y = i Z
+    #[test]
+    fn test_mul() {
+        let x =
+            ComplexMatrix::from_rows(vec![vec![c(0., 0.), c(1., 0.)], vec![c(1., 0.), c(0., 0.)]]);
+        let y = ComplexMatrix::from_rows(vec![
+            vec![c(0., 0.), c(0., -1.)],
+            vec![c(0., 1.), c(0., 0.)],
+        ]);
+        let iz = ComplexMatrix::from_rows(vec![
+            vec![c(0., 1.), c(0., 0.)],
+            vec![c(0., 0.), c(0., -1.)],
+        ]);
+        assert_close(&iz, &(&x * &y));
+        assert_close(&ComplexMatrix::identity(2), &(&x * &x));
+        assert_eq!(
+            vec![c(0., -1.), c(0., 0.)],
+            y.mul_vec(&[c(0., 0.), c(1., 0.)])
+        );
+    }
+
+    // add, sub and indexing
+    #[test]
+    fn test_add_sub_index() {
+        let mut a = ComplexMatrix::zeros(2, 3);
+        a[(1, 2)] = Complex::new(1, 1);
+        let b = ComplexMatrix::from_rows(vec![
+            vec![Complex::new(1, 0), Complex::new(0, 0), Complex::new(0, 0)],
+            vec![Complex::new(0, 0), Complex::new(0, 0), Complex::new(2, 0)],
+        ]);
+        let sum = &a + &b;
+        assert_eq!(Complex::new(3, 1), sum[(1, 2)]);
+        assert_eq!(a, sum - b);
+    }
+
+    // conjugate transpose swaps indices and conjugates
+    #[test]
+    fn test_conjugate_transpose() {
+        let a = ComplexMatrix::from_rows(vec![vec![
+            Complex::new(1, 2),
+            Complex::new(3, 4),
+            Complex::new(5, 6),
+        ]]);
+        let h = a.conjugate_transpose();
+        assert_eq!((3, 1), (h.rows(), h.cols()));
+        assert_eq!(Complex::new(3, -4), h[(1, 0)]);
+        assert_eq!(Complex::new(3, 4), a.transpose()[(1, 0)]);
+    }
+
+    // det [[1, i], [2, 3]] = 3 - 2i
+    #[test]
+    fn test_determinant() {
+        let a =
+            ComplexMatrix::from_rows(vec![vec![c(1., 0.), c(0., 1.)], vec![c(2., 0.), c(3., 0.)]]);
+        assert!((a.determinant() - c(3., -2.)).norm() < 1e-12);
+        // needs a row swap, so the sign flips
+        let b =
+            ComplexMatrix::from_rows(vec![vec![c(0., 0.), c(1., 0.)], vec![c(1., 0.), c(0., 0.)]]);
+        assert!((b.determinant() - c(-1., 0.)).norm() < 1e-12);
+    }
+
+    // P A = L U
+    #[test]
+    fn test_lu() {
+        let a = ComplexMatrix::from_rows(vec![
+            vec![c(1., 1.), c(2., 0.), c(0., 3.)],
+            vec![c(4., 0.), c(0., -1.), c(1., 1.)],
+            vec![c(0., 2.), c(1., 0.), c(2., -2.)],
+        ]);
+        let lu = a.lu();
+        let mut pa = ComplexMatrix::zeros(3, 3);
+        for (i, &p) in lu.permutation().iter().enumerate() {
+            for j in 0..3 {
+                pa[(i, j)] = a[(p, j)];
+            }
+        }
+        assert_close(&pa, &(&lu.l() * &lu.u()));
+    }
+
+    // A x = b and A A^-1 = I
+    #[test]
+    fn test_solve_inverse() {
+        let a = ComplexMatrix::from_rows(vec![
+            vec![c(1., 1.), c(2., 0.), c(0., 3.)],
+            vec![c(4., 0.), c(0., -1.), c(1., 1.)],
+            vec![c(0., 2.), c(1., 0.), c(2., -2.)],
+        ]);
+        let b = vec![c(1., 0.), c(0., 1.), c(-1., 2.)];
+        let x = a.solve(&b).unwrap();
+        for (e, r) in b.iter().zip(a.mul_vec(&x)) {
+            assert!((*e - r).norm() < 1e-12);
+        }
+        let inv = a.inverse().unwrap();
+        assert_close(&ComplexMatrix::identity(3), &(&a * &inv));
+    }
+
+    // singular matrices have zero determinant and no inverse
+    #[test]
+    fn test_singular() {
+        let a =
+            ComplexMatrix::from_rows(vec![vec![c(1., 1.), c(2., 2.)], vec![c(2., 2.), c(4., 4.)]]);
+        assert!(a.lu().is_singular());
+        assert_eq!(c(0., 0.), a.determinant());
+        assert_eq!(None, a.solve(&[c(1., 0.), c(0., 0.)]));
+        assert_eq!(None, a.inverse());
+    }
+
+    // singular only up to rounding, the last pivot is about 1e-16
+    #[test]
+    fn test_singular_rounding() {
+        let a = ComplexMatrix::from_rows(
+            (0..3)
+                .map(|i| (1..=3).map(|j| c((3 * i + j) as f64, 0.)).collect())
+                .collect(),
+        );
+        assert!(a.lu().is_singular());
+        assert_eq!(c(0., 0.), a.determinant());
+        assert_eq!(None, a.solve(&[c(1., 0.), c(0., 0.), c(0., 0.)]));
+        assert_eq!(None, a.inverse());
+        // a close but regular matrix still solves
+        let mut b = a.clone();
+        b[(2, 2)] = c(10., 0.);
+        assert!(b.solve(&[c(1., 0.), c(0., 0.), c(0., 0.)]).is_some());
+    }
+
+    // eigenvalues of 2x2 Hermitian matrices
+    #[test]
+    fn test_eigenvalues_2x2() {
+        let y = ComplexMatrix::from_rows(vec![
+            vec![c(0., 0.), c(0., -1.)],
+            vec![c(0., 1.), c(0., 0.)],
+        ]);
+        let ev = y.hermitian_eigenvalues();
+        assert!((ev[0] + 1.0).abs() < 1e-12 && (ev[1] - 1.0).abs() < 1e-12);
+        // trace 5 and determinant 4 give 1 and 4
+        let a = ComplexMatrix::from_rows(vec![
+            vec![c(2., 0.), c(1., -1.)],
+            vec![c(1., 1.), c(3., 0.)],
+        ]);
+        assert!(a.is_hermitian(0.0));
+        let ev = a.hermitian_eigenvalues();
+        assert!((ev[0] - 1.0).abs() < 1e-12 && (ev[1] - 4.0).abs() < 1e-12);
+    }
+
+    // eigenvalues of a larger Hermitian matrix satisfy det(A - l I) = 0
+    // and sum up to the trace
+    #[test]
+    fn test_eigenvalues_4x4() {
+        let a = ComplexMatrix::from_rows(vec![
+            vec![c(4., 0.), c(1., 2.), c(0., -1.), c(2., 0.)],
+            vec![c(1., -2.), c(3., 0.), c(1., 1.), c(0., 0.5)],
+            vec![c(0., 1.), c(1., -1.), c(-2., 0.), c(1., 0.)],
+            vec![c(2., 0.), c(0., -0.5), c(1., 0.), c(1., 0.)],
+        ]);
+        assert!(a.is_hermitian(1e-12));
+        let ev = a.hermitian_eigenvalues();
+        assert_eq!(4, ev.len());
+        assert!((ev.iter().sum::<f64>() - 6.0).abs() < 1e-9);
+        for l in ev {
+            let mut shifted = a.clone();
+            for i in 0..4 {
+                shifted[(i, i)] -= l;
+            }
+            assert!(shifted.determinant().norm() < 1e-8);
+        }
+    }
+
+    // multiplying incompatible shapes is a bug
+    #[test]
+    #[should_panic]
+    fn test_dimension_mismatch() {
+        let _ = &ComplexMatrix::<f64>::zeros(2, 3) * &ComplexMatrix::zeros(2, 3);
+    }
+}
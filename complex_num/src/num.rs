@@ -0,0 +1,270 @@
+// Numeric scalar trait used for the parts of a Complex number
+// it collects the operator traits that Complex arithmetic needs
+// and the two constants (zero and one) that cannot be written
+// generically as literals
+// only Clone is required so that heap allocated scalars like BigInt
+// and Rational work too, Float and Int add Copy for the primitives
+
+use std::fmt::{Debug, Display};
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+
+pub trait Num:
+    Clone
+    + Debug
+    + Display
+    + PartialEq
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+{
+    // additive identity
+    fn zero() -> Self;
+    // multiplicative identity
+    fn one() -> Self;
+    // lossy conversion, used to print any number in polar form
+    fn to_f64(self) -> f64;
+    // round towards zero, integers are already whole numbers
+    fn trunc(self) -> Self;
+}
+
+// implement Num for all the primitive integer and float types
+// integer literals and float literals need different spelling
+// so the macro takes the zero and one values explicitly
+// together with the body of trunc
+macro_rules! impl_num {
+    ($zero:expr, $one:expr, |$x:ident| $trunc:expr; $($t:ty),*) => {
+        $(
+            impl Num for $t {
+                fn zero() -> Self {
+                    $zero
+                }
+                fn one() -> Self {
+                    $one
+                }
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+                fn trunc(self) -> Self {
+                    let $x = self;
+                    $trunc
+                }
+            }
+        )*
+    };
+}
+
+impl_num!(0, 1, |x| x; i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+impl_num!(0.0, 1.0, |x| x.trunc(); f32, f64);
+
+// Floating point scalar trait
+// transcendental functions (exp, ln, sin, ...) of a Complex number
+// are built out of the real valued versions of those functions
+// which only exist for f32 and f64
+pub trait Float: Num + Copy + Neg<Output = Self> {
+    fn pi() -> Self;
+    // lossy conversion, constants computed in f64 (e.g. FFT twiddles)
+    // are converted with this into the scalar type
+    fn from_f64(x: f64) -> Self;
+    // difference between 1 and the next larger number
+    fn epsilon() -> Self;
+    fn is_finite(self) -> bool;
+    fn abs(self) -> Self;
+    fn sqrt(self) -> Self;
+    fn exp(self) -> Self;
+    fn ln(self) -> Self;
+    fn powf(self, n: Self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn sinh(self) -> Self;
+    fn cosh(self) -> Self;
+    fn atan2(self, other: Self) -> Self;
+    fn hypot(self, other: Self) -> Self;
+    fn copysign(self, sign: Self) -> Self;
+    // number of representable values between self and other,
+    // 0 and -0 are the same value, None if either is NaN
+    // the default estimates it from epsilon (see estimate_ulps),
+    // f32 and f64 count the exact number from their bits
+    fn ulps_between(self, other: Self) -> Option<u64> {
+        estimate_ulps(self, other)
+    }
+}
+
+// |a - b| in units of epsilon * max(|a|, |b|), the spacing of the
+// representable values near the larger number (within a factor of 2)
+// the numbers are compared as f64, infinite numbers are u64::MAX
+// apart from everything but themselves
+pub(crate) fn estimate_ulps<T: Float>(a: T, b: T) -> Option<u64> {
+    let (a, b, eps) = (a.to_f64(), b.to_f64(), T::epsilon().to_f64());
+    if a.is_nan() || b.is_nan() {
+        return None;
+    }
+    if a == b {
+        return Some(0);
+    }
+    if a.is_infinite() || b.is_infinite() {
+        return Some(u64::MAX);
+    }
+    let spacing = a.abs().max(b.abs()) * eps;
+    Some(((a - b).abs() / spacing).ceil() as u64)
+}
+
+// every method simply forwards to the inherent method
+// of the same name on the primitive float type
+// $bits is the signed integer type of the same width
+macro_rules! impl_float {
+    ($($t:ident => $bits:ty),*) => {
+        $(
+            impl Float for $t {
+                fn pi() -> Self {
+                    std::$t::consts::PI
+                }
+                fn from_f64(x: f64) -> Self {
+                    x as $t
+                }
+                fn epsilon() -> Self {
+                    $t::EPSILON
+                }
+                fn is_finite(self) -> bool {
+                    $t::is_finite(self)
+                }
+                fn abs(self) -> Self {
+                    $t::abs(self)
+                }
+                fn sqrt(self) -> Self {
+                    $t::sqrt(self)
+                }
+                fn exp(self) -> Self {
+                    $t::exp(self)
+                }
+                fn ln(self) -> Self {
+                    $t::ln(self)
+                }
+                fn powf(self, n: Self) -> Self {
+                    $t::powf(self, n)
+                }
+                fn sin(self) -> Self {
+                    $t::sin(self)
+                }
+                fn cos(self) -> Self {
+                    $t::cos(self)
+                }
+                fn sinh(self) -> Self {
+                    $t::sinh(self)
+                }
+                fn cosh(self) -> Self {
+                    $t::cosh(self)
+                }
+                fn atan2(self, other: Self) -> Self {
+                    $t::atan2(self, other)
+                }
+                fn hypot(self, other: Self) -> Self {
+                    $t::hypot(self, other)
+                }
+                fn copysign(self, sign: Self) -> Self {
+                    $t::copysign(self, sign)
+                }
+                fn ulps_between(self, other: Self) -> Option<u64> {
+                    if self.is_nan() || other.is_nan() {
+                        return None;
+                    }
+                    // map the bits to integers ordered like the floats,
+                    // negative floats count down from -0 = 0
+                    let ordered = |x: $t| {
+                        let b = x.to_bits() as $bits;
+                        if b < 0 {
+                            <$bits>::MIN as i128 - b as i128
+                        } else {
+                            b as i128
+                        }
+                    };
+                    Some((ordered(self) - ordered(other)).unsigned_abs() as u64)
+                }
+            }
+        )*
+    };
+}
+
+impl_float!(f32 => i32, f64 => i64);
+
+// Integer scalar trait
+// exposes the checked, wrapping and saturating versions of the
+// primitive operators so that Complex can build its own versions
+// of these operations on top of them
+pub trait Int: Num + Copy + Eq + Ord + Rem<Output = Self> {
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn checked_div(self, rhs: Self) -> Option<Self>;
+    fn wrapping_add(self, rhs: Self) -> Self;
+    fn wrapping_sub(self, rhs: Self) -> Self;
+    fn wrapping_mul(self, rhs: Self) -> Self;
+    fn wrapping_div(self, rhs: Self) -> Self;
+    fn saturating_add(self, rhs: Self) -> Self;
+    fn saturating_sub(self, rhs: Self) -> Self;
+    fn saturating_mul(self, rhs: Self) -> Self;
+    fn saturating_div(self, rhs: Self) -> Self;
+}
+
+// same as for Float all methods forward to the inherent methods
+macro_rules! impl_int {
+    ($($t:ident),*) => {
+        $(
+            impl Int for $t {
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    $t::checked_add(self, rhs)
+                }
+                fn checked_sub(self, rhs: Self) -> Option<Self> {
+                    $t::checked_sub(self, rhs)
+                }
+                fn checked_mul(self, rhs: Self) -> Option<Self> {
+                    $t::checked_mul(self, rhs)
+                }
+                fn checked_div(self, rhs: Self) -> Option<Self> {
+                    $t::checked_div(self, rhs)
+                }
+                fn wrapping_add(self, rhs: Self) -> Self {
+                    $t::wrapping_add(self, rhs)
+                }
+                fn wrapping_sub(self, rhs: Self) -> Self {
+                    $t::wrapping_sub(self, rhs)
+                }
+                fn wrapping_mul(self, rhs: Self) -> Self {
+                    $t::wrapping_mul(self, rhs)
+                }
+                fn wrapping_div(self, rhs: Self) -> Self {
+                    $t::wrapping_div(self, rhs)
+                }
+                fn saturating_add(self, rhs: Self) -> Self {
+                    $t::saturating_add(self, rhs)
+                }
+                fn saturating_sub(self, rhs: Self) -> Self {
+                    $t::saturating_sub(self, rhs)
+                }
+                fn saturating_mul(self, rhs: Self) -> Self {
+                    $t::saturating_mul(self, rhs)
+                }
+                fn saturating_div(self, rhs: Self) -> Self {
+                    $t::saturating_div(self, rhs)
+                }
+            }
+        )*
+    };
+}
+
+impl_int!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+// Signed integer scalar trait
+// Gaussian integer algorithms need exact integer division with
+// remainder and negation to move between the associates of a
+// number (multiplying with -1, i and -i)
+// unlike Int it does not need Copy or a fixed width, so BigInt has it
+pub trait SignedInt: Num + Eq + Ord + Rem<Output = Self> + Neg<Output = Self> {}
+
+impl SignedInt for i8 {}
+impl SignedInt for i16 {}
+impl SignedInt for i32 {}
+impl SignedInt for i64 {}
+impl SignedInt for i128 {}
+impl SignedInt for isize {}
@@ -0,0 +1,345 @@
+// More operators for Complex on top of the basic +, -, *, / in lib.rs
+// - negation, -z
+// - remainder, z % w
+// - the same operators on references, &z + &w
+// - mixed operators with a real scalar, z * 3 and 2 + z
+// - assignment operators, z += w, z *= 2
+// - Sum and Product of iterators over Complex
+
+use crate::{Complex, Num};
+use std::iter::{Product, Sum};
+use std::ops::{
+    Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign,
+};
+
+// implement Neg trait so that -z can be written
+// only for scalars which can be negated themselves
+impl<T: Num + Neg<Output = T>> Neg for Complex<T> {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Self::new(-self.real, -self.imag)
+    }
+}
+
+impl<T: Num + Neg<Output = T>> Neg for &Complex<T> {
+    type Output = Complex<T>;
+    fn neg(self) -> Self::Output {
+        -self.clone()
+    }
+}
+
+// implement Rem trait so that remainder operation can be done on Complex
+// z % w = z - q * w where q is z / w with both parts truncated towards zero
+// Note: this is the truncating remainder which is cheap and works for
+// every scalar, use div_rem for the Euclidean remainder of Gaussian integers
+impl<T: Num> Rem for Complex<T> {
+    type Output = Self;
+    fn rem(self, rhs: Self) -> Self::Output {
+        let q = self.clone() / rhs.clone();
+        let q = Self::new(q.real.trunc(), q.imag.trunc());
+        self - q * rhs
+    }
+}
+
+// implement the binary operators for every combination of
+// value and reference operands by cloning the referenced numbers
+macro_rules! forward_ref_binop {
+    ($($imp:ident, $method:ident;)*) => {
+        $(
+            impl<T: Num> $imp<&Complex<T>> for Complex<T> {
+                type Output = Complex<T>;
+                fn $method(self, rhs: &Complex<T>) -> Self::Output {
+                    $imp::$method(self, rhs.clone())
+                }
+            }
+
+            impl<T: Num> $imp<Complex<T>> for &Complex<T> {
+                type Output = Complex<T>;
+                fn $method(self, rhs: Complex<T>) -> Self::Output {
+                    $imp::$method(self.clone(), rhs)
+                }
+            }
+
+            impl<T: Num> $imp<&Complex<T>> for &Complex<T> {
+                type Output = Complex<T>;
+                fn $method(self, rhs: &Complex<T>) -> Self::Output {
+                    $imp::$method(self.clone(), rhs.clone())
+                }
+            }
+
+            impl<T: Num> $imp<T> for &Complex<T> {
+                type Output = Complex<T>;
+                fn $method(self, rhs: T) -> Self::Output {
+                    $imp::$method(self.clone(), rhs)
+                }
+            }
+        )*
+    };
+}
+
+forward_ref_binop! {
+    Add, add;
+    Sub, sub;
+    Mul, mul;
+    Div, div;
+    Rem, rem;
+}
+
+// (a+bi) + x = (a+x) + bi
+impl<T: Num> Add<T> for Complex<T> {
+    type Output = Self;
+    fn add(self, rhs: T) -> Self::Output {
+        Self::new(self.real + rhs, self.imag)
+    }
+}
+
+// (a+bi) - x = (a-x) + bi
+impl<T: Num> Sub<T> for Complex<T> {
+    type Output = Self;
+    fn sub(self, rhs: T) -> Self::Output {
+        Self::new(self.real - rhs, self.imag)
+    }
+}
+
+// (a+bi) * x = ax + bxi
+impl<T: Num> Mul<T> for Complex<T> {
+    type Output = Self;
+    fn mul(self, rhs: T) -> Self::Output {
+        Self::new(self.real * rhs.clone(), self.imag * rhs)
+    }
+}
+
+// (a+bi) / x = a/x + (b/x)i
+impl<T: Num> Div<T> for Complex<T> {
+    type Output = Self;
+    fn div(self, rhs: T) -> Self::Output {
+        Self::new(self.real / rhs.clone(), self.imag / rhs)
+    }
+}
+
+// same as dividing by x + 0i
+impl<T: Num> Rem<T> for Complex<T> {
+    type Output = Self;
+    fn rem(self, rhs: T) -> Self::Output {
+        self % Self::new(rhs, T::zero())
+    }
+}
+
+// scalar on the left hand side, 2 + z
+// a blanket impl over T is not allowed by the orphan rules
+// so these are implemented for each primitive type
+macro_rules! impl_scalar_lhs {
+    ($($t:ty),*) => {
+        $(
+            impl Add<Complex<$t>> for $t {
+                type Output = Complex<$t>;
+                fn add(self, rhs: Complex<$t>) -> Self::Output {
+                    rhs + self
+                }
+            }
+
+            impl Sub<Complex<$t>> for $t {
+                type Output = Complex<$t>;
+                fn sub(self, rhs: Complex<$t>) -> Self::Output {
+                    Complex::new(self, 0 as $t) - rhs
+                }
+            }
+
+            impl Mul<Complex<$t>> for $t {
+                type Output = Complex<$t>;
+                fn mul(self, rhs: Complex<$t>) -> Self::Output {
+                    rhs * self
+                }
+            }
+
+            impl Div<Complex<$t>> for $t {
+                type Output = Complex<$t>;
+                fn div(self, rhs: Complex<$t>) -> Self::Output {
+                    Complex::new(self, 0 as $t) / rhs
+                }
+            }
+
+            impl Rem<Complex<$t>> for $t {
+                type Output = Complex<$t>;
+                fn rem(self, rhs: Complex<$t>) -> Self::Output {
+                    Complex::new(self, 0 as $t) % rhs
+                }
+            }
+        )*
+    };
+}
+
+impl_scalar_lhs!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64);
+
+// assignment operators z op= w are written in terms of z = z op w
+// for Complex, &Complex and scalar right hand sides
+// the old value is moved out with mem::replace so nothing is cloned
+macro_rules! impl_assign_op {
+    ($($imp:ident, $method:ident, $op:ident, $op_method:ident;)*) => {
+        $(
+            impl<T: Num> $imp for Complex<T> {
+                fn $method(&mut self, rhs: Self) {
+                    *self = $op::$op_method(take(self), rhs);
+                }
+            }
+
+            impl<T: Num> $imp<&Complex<T>> for Complex<T> {
+                fn $method(&mut self, rhs: &Self) {
+                    *self = $op::$op_method(take(self), rhs.clone());
+                }
+            }
+
+            impl<T: Num> $imp<T> for Complex<T> {
+                fn $method(&mut self, rhs: T) {
+                    *self = $op::$op_method(take(self), rhs);
+                }
+            }
+        )*
+    };
+}
+
+// leaves 0 behind, the caller overwrites it right away
+fn take<T: Num>(z: &mut Complex<T>) -> Complex<T> {
+    std::mem::replace(z, Complex::new(T::zero(), T::zero()))
+}
+
+impl_assign_op! {
+    AddAssign, add_assign, Add, add;
+    SubAssign, sub_assign, Sub, sub;
+    MulAssign, mul_assign, Mul, mul;
+    DivAssign, div_assign, Div, div;
+    RemAssign, rem_assign, Rem, rem;
+}
+
+// sum of an iterator, starting from 0
+impl<T: Num> Sum for Complex<T> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::new(T::zero(), T::zero()), |acc, n| acc + n)
+    }
+}
+
+impl<'a, T: Num> Sum<&'a Complex<T>> for Complex<T> {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.cloned().sum()
+    }
+}
+
+// product of an iterator, starting from 1
+impl<T: Num> Product for Complex<T> {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::new(T::one(), T::zero()), |acc, n| acc * n)
+    }
+}
+
+impl<'a, T: Num> Product<&'a Complex<T>> for Complex<T> {
+    fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.cloned().product()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    // negation flips both signs
+    #[test]
+    fn test_neg() {
+        let n = Complex::new(2, -5);
+        assert_eq!(Complex::new(-2, 5), -n);
+        assert_eq!(Complex::new(-2, 5), -&n);
+        assert_eq!(Complex::new(-0.5, -1.0), -Complex::new(0.5, 1.0));
+    }
+
+    // remainder truncates the quotient
+    #[test]
+    fn test_rem() {
+        // (7+5i)/(2+i) = 3.8 + 0.6i truncates to 3
+        let lhs = Complex::new(7, 5);
+        let rhs = Complex::new(2, 1);
+        assert_eq!(Complex::new(1, 2), lhs % rhs);
+        assert_eq!(Complex::new(1, 1), Complex::new(7, 5) % 2);
+        let lhs = Complex::new(7.5, 5.0);
+        assert_eq!(Complex::new(1.5, 1.0), lhs % 2.0);
+    }
+
+    // operators on references give the same results
+    // the references are taken on purpose here
+    #[test]
+    #[allow(clippy::op_ref)]
+    fn test_ref_ops() {
+        let lhs = Complex::new(2, 5);
+        let rhs = Complex::new(4, 7);
+        assert_eq!(lhs + rhs, &lhs + &rhs);
+        assert_eq!(lhs - rhs, lhs - &rhs);
+        assert_eq!(lhs * rhs, &lhs * rhs);
+        assert_eq!(lhs / rhs, &lhs / &rhs);
+        assert_eq!(lhs % rhs, &lhs % &rhs);
+        assert_eq!(lhs * 2, &lhs * 2);
+    }
+
+    // scalar on the right hand side
+    #[test]
+    fn test_scalar_rhs() {
+        let n = Complex::new(2, 5);
+        assert_eq!(Complex::new(5, 5), n + 3);
+        assert_eq!(Complex::new(-1, 5), n - 3);
+        assert_eq!(Complex::new(6, 15), n * 3);
+        assert_eq!(Complex::new(1, 2), n / 2);
+        assert_eq!(Complex::new(0.25, 0.5), Complex::new(0.5, 1.0) / 2.0);
+    }
+
+    // scalar on the left hand side
+    #[test]
+    fn test_scalar_lhs() {
+        let n = Complex::new(2, 5);
+        assert_eq!(Complex::new(5, 5), 3 + n);
+        assert_eq!(Complex::new(1, -5), 3 - n);
+        assert_eq!(Complex::new(6, 15), 3 * n);
+        assert_eq!(Complex::new(0.0, -0.5), 1.0 / Complex::new(0.0, 2.0));
+        assert_eq!(Complex::new(1u8, 0), 7u8 % Complex::new(2, 0));
+    }
+
+    // assignment operators
+    #[test]
+    fn test_assign_ops() {
+        let mut n = Complex::new(2, 5);
+        n += Complex::new(1, 1);
+        assert_eq!(Complex::new(3, 6), n);
+        n -= &Complex::new(1, 1);
+        assert_eq!(Complex::new(2, 5), n);
+        n *= Complex::new(0, 1);
+        assert_eq!(Complex::new(-5, 2), n);
+        n /= Complex::new(0, 1);
+        assert_eq!(Complex::new(2, 5), n);
+        n *= 2;
+        assert_eq!(Complex::new(4, 10), n);
+        n += 1;
+        assert_eq!(Complex::new(5, 10), n);
+        n %= 3;
+        assert_eq!(Complex::new(2, 1), n);
+    }
+
+    // sum and product of iterators
+    #[test]
+    fn test_sum_product() {
+        let v = vec![Complex::new(1, 2), Complex::new(3, 4), Complex::new(0, 1)];
+        assert_eq!(Complex::new(4, 7), v.iter().sum());
+        assert_eq!(Complex::new(-10, -5), v.iter().product());
+        assert_eq!(Complex::new(4, 7), v.into_iter().sum());
+        let empty: Vec<Complex<i32>> = vec![];
+        assert_eq!(Complex::new(0, 0), empty.iter().sum());
+        assert_eq!(Complex::new(1, 0), empty.iter().product());
+    }
+
+    // derived traits
+    #[test]
+    fn test_derived() {
+        let n = Complex::new(2, 5);
+        let m = n;
+        assert_eq!(n, Clone::clone(&m));
+        assert_eq!(Complex::new(0, 0), Complex::default());
+        let set: HashSet<Complex<i32>> = [n, m, Complex::new(1, 1)].into_iter().collect();
+        assert_eq!(2, set.len());
+    }
+}
@@ -0,0 +1,169 @@
+// Parse Complex numbers from strings
+// accepts every string produced by Display ("2 - 3i", "3i", "0")
+// and a few more common spellings:
+// - j as imaginary unit, "3+4j"
+// - unit coefficient omitted, "i", "-i", "2 + i"
+// - exponent notation for float parts, "2.5e3-1i"
+// - any whitespace around the terms and the + or - between them
+// each part is parsed with the FromStr impl of the scalar type
+// so "2.5" is rejected for Complex<i32> and "-1" for Complex<u8>
+
+use crate::{Complex, Num, ParseComplexError, ParseErrorKind};
+use std::str::FromStr;
+
+impl<T: Num + FromStr> FromStr for Complex<T> {
+    type Err = ParseComplexError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let start = s.len() - s.trim_start().len();
+        let t = s.trim();
+        if t.is_empty() {
+            return Err(ParseComplexError::new(ParseErrorKind::Empty, s.len()));
+        }
+        let zero = T::zero();
+        match find_split(t) {
+            // single term, either real or imaginary
+            None if is_imag(t) => Ok(Self::new(zero, parse_imag(t, start)?)),
+            None => Ok(Self::new(parse_real(t, start)?, zero)),
+            // two terms, real part followed by imaginary part
+            Some(i) => {
+                let (re, im) = (t[..i].trim_end(), &t[i..]);
+                if is_imag(re) {
+                    let offset = start + re.len() - 1;
+                    return Err(ParseComplexError::new(
+                        ParseErrorKind::UnexpectedImaginaryUnit,
+                        offset,
+                    ));
+                }
+                if !is_imag(im) {
+                    let offset = start + t.len();
+                    return Err(ParseComplexError::new(
+                        ParseErrorKind::MissingImaginaryUnit,
+                        offset,
+                    ));
+                }
+                Ok(Self::new(
+                    parse_real(re, start)?,
+                    parse_imag(im, start + i)?,
+                ))
+            }
+        }
+    }
+}
+
+// position of the + or - separating the real and imaginary terms
+// a sign at the very beginning belongs to the first term
+// and a sign right after the e of an exponent (1e-5) belongs to the number
+fn find_split(t: &str) -> Option<usize> {
+    let b = t.as_bytes();
+    (1..b.len()).find(|&i| {
+        let is_sign = b[i] == b'+' || b[i] == b'-';
+        let is_exponent = i >= 2
+            && (b[i - 1] == b'e' || b[i - 1] == b'E')
+            && (b[i - 2].is_ascii_digit() || b[i - 2] == b'.');
+        is_sign && !is_exponent
+    })
+}
+
+fn is_imag(term: &str) -> bool {
+    term.ends_with(['i', 'j'])
+}
+
+// parse a real term, the whole term must be a number
+fn parse_real<T: FromStr>(term: &str, offset: usize) -> Result<T, ParseComplexError> {
+    term.parse()
+        .map_err(|_| ParseComplexError::new(ParseErrorKind::InvalidNumber, offset))
+}
+
+// parse an imaginary term like "3i", "- 2.5j" or "-i"
+// the sign may be separated from the number by whitespace
+// and a missing number means 1
+fn parse_imag<T: FromStr>(term: &str, offset: usize) -> Result<T, ParseComplexError> {
+    let body = &term[..term.len() - 1];
+    let (sign, rest) = match body.strip_prefix(['+', '-']) {
+        Some(rest) => (&body[..1], rest.trim_start()),
+        None => ("", body),
+    };
+    let digits = if rest.is_empty() { "1" } else { rest };
+    format!("{sign}{digits}")
+        .parse()
+        .map_err(|_| ParseComplexError::new(ParseErrorKind::InvalidNumber, offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck::{quickcheck, TestResult};
+
+    // every form printed by Display
+    #[test]
+    fn test_parse_display_forms() {
+        assert_eq!(Ok(Complex::new(2, 3)), "2 + 3i".parse());
+        assert_eq!(Ok(Complex::new(-2, 3)), "-2 + 3i".parse());
+        assert_eq!(Ok(Complex::new(2, -3)), "2 - 3i".parse());
+        assert_eq!(Ok(Complex::new(-2, -3)), "-2 - 3i".parse());
+        assert_eq!(Ok(Complex::new(2, 0)), "2".parse());
+        assert_eq!(Ok(Complex::new(0, 3)), "3i".parse());
+        assert_eq!(Ok(Complex::new(0, -3)), "-3i".parse());
+        assert_eq!(Ok(Complex::new(0, 0)), "0".parse());
+    }
+
+    // other accepted spellings
+    #[test]
+    fn test_parse_variants() {
+        assert_eq!(Ok(Complex::new(3, 4)), "3+4j".parse());
+        assert_eq!(Ok(Complex::new(0, -1)), "-i".parse());
+        assert_eq!(Ok(Complex::new(0, 1)), "i".parse());
+        assert_eq!(Ok(Complex::new(2, 1)), "2 + i".parse());
+        assert_eq!(Ok(Complex::new(2, -1)), "  2-  i ".parse());
+        assert_eq!(Ok(Complex::new(2500.0, -1.0)), "2.5e3-1i".parse());
+        assert_eq!(Ok(Complex::new(1e-5, 2e+3)), "1e-5+2E+3j".parse());
+        assert_eq!(Ok(Complex::new(0.5, 0.0)), "\t+0.5\n".parse());
+    }
+
+    // errors carry the kind and the byte offset
+    #[test]
+    fn test_parse_errors() {
+        let err = "  ".parse::<Complex<i32>>().unwrap_err();
+        assert_eq!(ParseErrorKind::Empty, err.kind());
+        let err = "2 + xi".parse::<Complex<i32>>().unwrap_err();
+        assert_eq!(ParseErrorKind::InvalidNumber, err.kind());
+        assert_eq!(2, err.offset());
+        let err = " abc".parse::<Complex<i32>>().unwrap_err();
+        assert_eq!(ParseErrorKind::InvalidNumber, err.kind());
+        assert_eq!(1, err.offset());
+        let err = "2 + 3".parse::<Complex<i32>>().unwrap_err();
+        assert_eq!(ParseErrorKind::MissingImaginaryUnit, err.kind());
+        assert_eq!(5, err.offset());
+        let err = "2i + 3i".parse::<Complex<i32>>().unwrap_err();
+        assert_eq!(ParseErrorKind::UnexpectedImaginaryUnit, err.kind());
+        assert_eq!(1, err.offset());
+        let err = "2.5 + 1i".parse::<Complex<i32>>().unwrap_err();
+        assert_eq!(ParseErrorKind::InvalidNumber, err.kind());
+        assert_eq!(0, err.offset());
+        let err = "-i".parse::<Complex<u8>>().unwrap_err();
+        assert_eq!(ParseErrorKind::InvalidNumber, err.kind());
+        assert_eq!("invalid number at byte 0", err.to_string());
+    }
+
+    // parse(to_string(x)) == x for any parts
+    quickcheck! {
+        fn prop_roundtrip_i32(real: i32, imag: i32) -> bool {
+            let n = Complex::new(real, imag);
+            n.to_string().parse() == Ok(n)
+        }
+
+        fn prop_roundtrip_u64(real: u64, imag: u64) -> bool {
+            let n = Complex::new(real, imag);
+            n.to_string().parse() == Ok(n)
+        }
+
+        fn prop_roundtrip_f64(real: f64, imag: f64) -> TestResult {
+            if real.is_nan() || imag.is_nan() {
+                return TestResult::discard();
+            }
+            let n = Complex::new(real, imag);
+            TestResult::from_bool(n.to_string().parse() == Ok(n))
+        }
+    }
+}
@@ -0,0 +1,437 @@
+// Polynomials with Complex coefficients
+// p(x) = c[0] + c[1] x + c[2] x^2 + ... + c[n] x^n
+// coefficients are stored from the constant term upwards and
+// trailing zero coefficients are always trimmed, so the zero
+// polynomial has no coefficients at all
+// - evaluation with Horner's scheme
+// - +, -, * and division with remainder
+// - derivative
+// - all complex roots with the Aberth-Ehrlich iteration
+
+use crate::{Complex, Float, Num};
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::ops::{Add, Mul, Sub};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polynomial<T> {
+    coeffs: Vec<Complex<T>>,
+}
+
+impl<T: Num> Polynomial<T> {
+    // create a polynomial from coefficients, constant term first
+    pub fn new(coeffs: Vec<Complex<T>>) -> Self {
+        let mut p = Self { coeffs };
+        p.trim();
+        p
+    }
+
+    // monic polynomial (x - r0)(x - r1)... with the given roots
+    pub fn from_roots(roots: &[Complex<T>]) -> Self {
+        let one = Complex::new(T::one(), T::zero());
+        let mut p = Self::new(vec![one.clone()]);
+        for r in roots {
+            p = p * Self::new(vec![
+                Complex::new(T::zero(), T::zero()) - r.clone(),
+                one.clone(),
+            ]);
+        }
+        p
+    }
+
+    pub fn coeffs(&self) -> &[Complex<T>] {
+        &self.coeffs
+    }
+
+    // degree of the highest non zero term
+    // the zero polynomial has no degree
+    pub fn degree(&self) -> Option<usize> {
+        self.coeffs.len().checked_sub(1)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coeffs.is_empty()
+    }
+
+    // evaluate with Horner's scheme
+    // c[0] + x(c[1] + x(c[2] + ...))
+    pub fn eval(&self, x: Complex<T>) -> Complex<T> {
+        let zero = Complex::new(T::zero(), T::zero());
+        self.coeffs
+            .iter()
+            .rev()
+            .fold(zero, |acc, c| acc * x.clone() + c.clone())
+    }
+
+    // d/dx c[k] x^k = k c[k] x^(k-1)
+    pub fn derivative(&self) -> Self {
+        let mut k = T::zero();
+        let coeffs = self
+            .coeffs
+            .iter()
+            .skip(1)
+            .map(|c| {
+                k = k.clone() + T::one();
+                c.clone() * k.clone()
+            })
+            .collect();
+        Self::new(coeffs)
+    }
+
+    // polynomial long division, returns (quotient, remainder)
+    // so that self = quotient * divisor + remainder
+    // and the remainder has a lower degree than the divisor
+    // Note: panics if the divisor is the zero polynomial
+    pub fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+        let d = divisor.degree().expect("division by zero polynomial");
+        let lead = divisor.coeffs[d].clone();
+        let mut rem = self.coeffs.clone();
+        let zero = Complex::new(T::zero(), T::zero());
+        if rem.len() <= d {
+            return (Self::new(vec![]), self.clone());
+        }
+        let mut quot = vec![zero; rem.len() - d];
+        for k in (0..quot.len()).rev() {
+            let q = rem[k + d].clone() / lead.clone();
+            quot[k] = q.clone();
+            for (j, c) in divisor.coeffs.iter().enumerate() {
+                rem[k + j] -= q.clone() * c.clone();
+            }
+        }
+        rem.truncate(d);
+        (Self::new(quot), Self::new(rem))
+    }
+
+    fn trim(&mut self) {
+        let zero = Complex::new(T::zero(), T::zero());
+        while self.coeffs.last() == Some(&zero) {
+            self.coeffs.pop();
+        }
+    }
+}
+
+impl<T: Float> Polynomial<T> {
+    // all roots, counted with multiplicity, using the Aberth-Ehrlich method
+    // every root estimate z is moved by
+    //   w / (1 - w * sum 1 / (z - z_j)),  w = p(z) / p'(z)
+    // which is Newton's step corrected by the repulsion from the other
+    // estimates so that they do not converge to the same root
+    // the zero polynomial and constants have no roots
+    pub fn roots(&self) -> Vec<Complex<T>> {
+        let n = match self.degree() {
+            Some(n) if n > 0 => n,
+            _ => return vec![],
+        };
+        let dp = self.derivative();
+        // all roots lie within the Cauchy bound 1 + max |c[k] / c[n]|
+        // start on a circle of that radius, rotated off the real axis
+        // so that real polynomials do not start on a symmetry line
+        let lead = self.coeffs[n];
+        let bound = self.coeffs[..n]
+            .iter()
+            .map(|c| (*c / lead).norm())
+            .fold(T::zero(), |m, r| if r > m { r } else { m });
+        let radius = T::one() + bound;
+        let mut z: Vec<Complex<T>> = (0..n)
+            .map(|k| {
+                let theta = 2.0 * std::f64::consts::PI * k as f64 / n as f64 + 0.4;
+                Complex::from_polar(radius, T::from_f64(theta))
+            })
+            .collect();
+        let tolerance = T::epsilon() * T::from_f64(16.0);
+        let one = Complex::new(T::one(), T::zero());
+        for _ in 0..500 {
+            let mut converged = true;
+            for i in 0..n {
+                let pz = self.eval(z[i]);
+                if pz.norm() == T::zero() {
+                    continue;
+                }
+                let w = pz / dp.eval(z[i]);
+                let repulsion: Complex<T> = (0..n)
+                    .filter(|&j| j != i)
+                    .map(|j| one / (z[i] - z[j]))
+                    .sum();
+                let step = w / (one - w * repulsion);
+                // a multiple root can make p'(z) vanish, skip the step then
+                if step.real.is_finite() && step.imag.is_finite() {
+                    z[i] -= step;
+                    if step.norm() > tolerance * (T::one() + z[i].norm()) {
+                        converged = false;
+                    }
+                }
+            }
+            if converged {
+                break;
+            }
+        }
+        z
+    }
+}
+
+// c[0] + c[1] + ... term by term, the shorter one padded with zeros
+impl<T: Num> Add for Polynomial<T> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        &self + &rhs
+    }
+}
+
+impl<T: Num> Add for &Polynomial<T> {
+    type Output = Polynomial<T>;
+    fn add(self, rhs: Self) -> Self::Output {
+        let zero = Complex::new(T::zero(), T::zero());
+        let len = self.coeffs.len().max(rhs.coeffs.len());
+        let coeffs = (0..len)
+            .map(|k| {
+                let a = self.coeffs.get(k).cloned().unwrap_or(zero.clone());
+                let b = rhs.coeffs.get(k).cloned().unwrap_or(zero.clone());
+                a + b
+            })
+            .collect();
+        Polynomial::new(coeffs)
+    }
+}
+
+impl<T: Num> Sub for Polynomial<T> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        &self - &rhs
+    }
+}
+
+impl<T: Num> Sub for &Polynomial<T> {
+    type Output = Polynomial<T>;
+    fn sub(self, rhs: Self) -> Self::Output {
+        let zero = Complex::new(T::zero(), T::zero());
+        let len = self.coeffs.len().max(rhs.coeffs.len());
+        let coeffs = (0..len)
+            .map(|k| {
+                let a = self.coeffs.get(k).cloned().unwrap_or(zero.clone());
+                let b = rhs.coeffs.get(k).cloned().unwrap_or(zero.clone());
+                a - b
+            })
+            .collect();
+        Polynomial::new(coeffs)
+    }
+}
+
+// product is the convolution of the coefficients
+impl<T: Num> Mul for Polynomial<T> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self::Output {
+        &self * &rhs
+    }
+}
+
+impl<T: Num> Mul for &Polynomial<T> {
+    type Output = Polynomial<T>;
+    fn mul(self, rhs: Self) -> Self::Output {
+        if self.is_zero() || rhs.is_zero() {
+            return Polynomial::new(vec![]);
+        }
+        let zero = Complex::new(T::zero(), T::zero());
+        let mut coeffs = vec![zero; self.coeffs.len() + rhs.coeffs.len() - 1];
+        for (i, a) in self.coeffs.iter().enumerate() {
+            for (j, b) in rhs.coeffs.iter().enumerate() {
+                coeffs[i + j] += a.clone() * b.clone();
+            }
+        }
+        Polynomial::new(coeffs)
+    }
+}
+
+// print from the highest power down, e.g. (1 + 2i)x^2 + (3)x + (-1)
+// every coefficient is put in parentheses since it may have two parts
+impl<T: Num> Display for Polynomial<T> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+        let zero = Complex::new(T::zero(), T::zero());
+        let terms: Vec<String> = self
+            .coeffs
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, c)| **c != zero)
+            .map(|(k, c)| match k {
+                0 => format!("({c})"),
+                1 => format!("({c})x"),
+                _ => format!("({c})x^{k}"),
+            })
+            .collect();
+        write!(f, "{}", terms.join(" + "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_complex_eq;
+
+    fn c(real: f64, imag: f64) -> Complex<f64> {
+        Complex::new(real, imag)
+    }
+
+    // every expected root is found exactly once
+    fn assert_roots(expected: &[Complex<f64>], mut found: Vec<Complex<f64>>) {
+        assert_eq!(expected.len(), found.len());
+        for e in expected {
+            let (i, d) = found
+                .iter()
+                .map(|r| (*r - *e).norm())
+                .enumerate()
+                .fold((0, f64::MAX), |m, (i, d)| if d < m.1 { (i, d) } else { m });
+            assert!(d < 1e-6, "root {e} not found in {found:?}");
+            found.remove(i);
+        }
+    }
+
+    // trailing zeros are trimmed
+    #[test]
+    fn test_new_degree() {
+        let p = Polynomial::new(vec![c(1.0, 0.0), c(2.0, 0.0), c(0.0, 0.0)]);
+        assert_eq!(Some(1), p.degree());
+        assert_eq!(None, Polynomial::<f64>::new(vec![c(0.0, 0.0)]).degree());
+    }
+
+    // 1 + 2x + 3x^2 at x = i is 1 + 2i - 3
+    #[test]
+    fn test_eval() {
+        let p = Polynomial::new(vec![
+            Complex::new(1, 0),
+            Complex::new(2, 0),
+            Complex::new(3, 0),
+        ]);
+        assert_eq!(Complex::new(-2, 2), p.eval(Complex::new(0, 1)));
+        assert_eq!(
+            Complex::new(0, 0),
+            Polynomial::new(vec![]).eval(Complex::new(5, 5))
+        );
+    }
+
+    // addition, subtraction and multiplication
+    #[test]
+    fn test_arithmetic() {
+        let p = Polynomial::new(vec![Complex::new(1, 0), Complex::new(0, 1)]);
+        let q = Polynomial::new(vec![
+            Complex::new(-1, 0),
+            Complex::new(0, -1),
+            Complex::new(2, 0),
+        ]);
+        assert_eq!(
+            Polynomial::new(vec![
+                Complex::new(0, 0),
+                Complex::new(0, 0),
+                Complex::new(2, 0)
+            ]),
+            &p + &q
+        );
+        assert_eq!(
+            Polynomial::new(vec![
+                Complex::new(2, 0),
+                Complex::new(0, 2),
+                Complex::new(-2, 0)
+            ]),
+            &p - &q
+        );
+        // (1 + ix)(1 + ix) = 1 + 2ix - x^2
+        assert_eq!(
+            Polynomial::new(vec![
+                Complex::new(1, 0),
+                Complex::new(0, 2),
+                Complex::new(-1, 0)
+            ]),
+            p.clone() * p.clone()
+        );
+        // p - p is the zero polynomial
+        assert!((p.clone() - p).is_zero());
+    }
+
+    // derivative of 1 + 2x + 3x^2 is 2 + 6x
+    #[test]
+    fn test_derivative() {
+        let p = Polynomial::new(vec![
+            Complex::new(1, 0),
+            Complex::new(2, 0),
+            Complex::new(3, 0),
+        ]);
+        assert_eq!(
+            Polynomial::new(vec![Complex::new(2, 0), Complex::new(6, 0)]),
+            p.derivative()
+        );
+        assert!(Polynomial::new(vec![Complex::new(7, 0)])
+            .derivative()
+            .is_zero());
+    }
+
+    // quotient * divisor + remainder gives back the dividend
+    #[test]
+    fn test_div_rem() {
+        let p = Polynomial::new(vec![c(1.0, 1.0), c(-2.0, 0.0), c(0.0, 3.0), c(1.0, 0.0)]);
+        let d = Polynomial::new(vec![c(2.0, 0.0), c(1.0, -1.0)]);
+        let (q, r) = p.div_rem(&d);
+        assert_eq!(Some(2), q.degree());
+        assert!(r.degree().unwrap_or(0) < 1);
+        let back = &(&q * &d) + &r;
+        for (a, b) in back.coeffs().iter().zip(p.coeffs()) {
+            assert_complex_eq!(*a, *b, abs_tol = 1e-12, rel_tol = 0.0);
+        }
+        // dividing by a higher degree gives zero quotient
+        let (q, r) = d.div_rem(&p);
+        assert!(q.is_zero());
+        assert_eq!(d, r);
+    }
+
+    // x^2 + 1 has roots i and -i
+    #[test]
+    fn test_roots_quadratic() {
+        let p = Polynomial::new(vec![c(1.0, 0.0), c(0.0, 0.0), c(1.0, 0.0)]);
+        assert_roots(&[c(0.0, 1.0), c(0.0, -1.0)], p.roots());
+    }
+
+    // roots of a polynomial built from known complex roots
+    #[test]
+    fn test_roots_from_roots() {
+        let roots = [
+            c(1.0, 2.0),
+            c(-3.0, 0.5),
+            c(0.0, -1.0),
+            c(2.5, 0.0),
+            c(-1.0, -1.0),
+        ];
+        let p = Polynomial::from_roots(&roots);
+        assert_eq!(Some(5), p.degree());
+        assert_roots(&roots, p.roots());
+    }
+
+    // a double root is found twice
+    #[test]
+    fn test_roots_multiple() {
+        let roots = [c(1.0, 1.0), c(1.0, 1.0), c(-2.0, 0.0)];
+        let p = Polynomial::from_roots(&roots);
+        let found = p.roots();
+        assert_eq!(3, found.len());
+        for r in &found {
+            assert!(p.eval(*r).norm() < 1e-9);
+        }
+    }
+
+    // constants have no roots
+    #[test]
+    fn test_roots_constant() {
+        assert!(Polynomial::new(vec![c(3.0, 0.0)]).roots().is_empty());
+        assert!(Polynomial::<f64>::new(vec![]).roots().is_empty());
+    }
+
+    #[test]
+    fn test_display() {
+        let p = Polynomial::new(vec![
+            Complex::new(-1, 0),
+            Complex::new(3, 0),
+            Complex::new(1, 2),
+        ]);
+        assert_eq!("(1 + 2i)x^2 + (3)x + (-1)", p.to_string());
+        assert_eq!("0", Polynomial::<i32>::new(vec![]).to_string());
+    }
+}
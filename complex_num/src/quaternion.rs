@@ -0,0 +1,368 @@
+// Quaternion numbers w + xi + yj + zk
+// they extend the Complex numbers with two more imaginary units
+// where i^2 = j^2 = k^2 = ijk = -1
+// multiplication (the Hamilton product) is not commutative: ij = k but ji = -k
+// unit quaternions represent rotations in 3D space
+// - conjugate, norm and inverse
+// - +, -, * and / operators, q / p is q * p^-1
+// - rotation of 3D vectors and conversion from axis and angle
+// - slerp, spherical linear interpolation between two rotations
+// - display in the same layout as Complex, e.g. 1 + 2i - 3j + 4k
+
+use crate::fmt::{format_part, write_terms};
+use crate::{Complex, Float, Num};
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+// w is the real (scalar) part, x, y and z the imaginary (vector) part
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct Quaternion<T> {
+    pub w: T,
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T: Num> Quaternion<T> {
+    pub fn new(w: T, x: T, y: T, z: T) -> Self {
+        Self { w, x, y, z }
+    }
+
+    // quaternion with zero scalar part, used for vectors in rotations
+    pub fn from_vector(v: [T; 3]) -> Self {
+        let [x, y, z] = v;
+        Self::new(T::zero(), x, y, z)
+    }
+
+    // the imaginary part x, y, z as a 3D vector
+    pub fn vector(&self) -> [T; 3] {
+        [self.x.clone(), self.y.clone(), self.z.clone()]
+    }
+
+    // w - xi - yj - zk
+    // Note: overflows for unsigned scalars just like Complex::conjugate
+    pub fn conjugate(&self) -> Self {
+        let zero = T::zero();
+        Self::new(
+            self.w.clone(),
+            zero.clone() - self.x.clone(),
+            zero.clone() - self.y.clone(),
+            zero - self.z.clone(),
+        )
+    }
+
+    // four dimensional dot product
+    pub fn dot(&self, other: &Self) -> T {
+        self.w.clone() * other.w.clone()
+            + self.x.clone() * other.x.clone()
+            + self.y.clone() * other.y.clone()
+            + self.z.clone() * other.z.clone()
+    }
+
+    // square of the norm w^2 + x^2 + y^2 + z^2, q * conj(q)
+    pub fn norm_sqr(&self) -> T {
+        self.dot(self)
+    }
+
+    // q^-1 = conj(q) / |q|^2 so that q * q^-1 = 1
+    // Note: the inverse of 0 divides by zero like 1 / (0 + 0i)
+    pub fn inverse(&self) -> Self {
+        self.conjugate() / self.norm_sqr()
+    }
+}
+
+impl<T: Float> Quaternion<T> {
+    // |q| = sqrt(w^2 + x^2 + y^2 + z^2)
+    pub fn norm(&self) -> T {
+        self.w.hypot(self.x).hypot(self.y.hypot(self.z))
+    }
+
+    // q / |q|, a unit quaternion pointing the same way
+    pub fn normalize(&self) -> Self {
+        *self / self.norm()
+    }
+
+    // rotation by angle (radians) around axis
+    // the axis does not have to be of unit length
+    // q = cos(θ/2) + sin(θ/2)(axis / |axis|)
+    pub fn from_axis_angle(axis: [T; 3], angle: T) -> Self {
+        let half = angle / T::from_f64(2.0);
+        let v = Self::from_vector(axis).normalize();
+        Self::new(half.cos(), T::zero(), T::zero(), T::zero()) + v * half.sin()
+    }
+
+    // rotate the vector v by this quaternion, q v q^-1
+    // only unit quaternions are pure rotations, any other quaternion
+    // rotates by the same angle (the scaling cancels out)
+    pub fn rotate(&self, v: [T; 3]) -> [T; 3] {
+        (*self * Self::from_vector(v) * self.inverse()).vector()
+    }
+
+    // spherical linear interpolation between two unit quaternions
+    // t = 0 gives self, t = 1 gives other and values in between move
+    // along the shortest arc with constant angular velocity
+    pub fn slerp(&self, other: &Self, t: T) -> Self {
+        let one = T::one();
+        // q and -q are the same rotation, take the one on the short arc
+        let (other, cos) = match self.dot(other) {
+            cos if cos < T::zero() => (-*other, -cos),
+            cos => (*other, cos),
+        };
+        // clamp rounding errors that make 1 - cos^2 slightly negative
+        let sin_sqr = one - cos * cos;
+        let sin = if sin_sqr > T::zero() {
+            sin_sqr.sqrt()
+        } else {
+            T::zero()
+        };
+        // nearly the same rotation, sin θ is too small to divide by
+        if sin < T::epsilon().sqrt() {
+            return (*self * (one - t) + other * t).normalize();
+        }
+        let theta = sin.atan2(cos);
+        let a = ((one - t) * theta).sin() / sin;
+        let b = (t * theta).sin() / sin;
+        *self * a + other * b
+    }
+}
+
+// a + bi is the quaternion a + bi + 0j + 0k
+impl<T: Num> From<Complex<T>> for Quaternion<T> {
+    fn from(c: Complex<T>) -> Self {
+        Self::new(c.real, c.imag, T::zero(), T::zero())
+    }
+}
+
+impl<T: Num> Add for Quaternion<T> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(
+            self.w + rhs.w,
+            self.x + rhs.x,
+            self.y + rhs.y,
+            self.z + rhs.z,
+        )
+    }
+}
+
+impl<T: Num> Sub for Quaternion<T> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(
+            self.w - rhs.w,
+            self.x - rhs.x,
+            self.y - rhs.y,
+            self.z - rhs.z,
+        )
+    }
+}
+
+// Hamilton product, expand (a + bi + cj + dk)(e + fi + gj + hk)
+// with ij = k, jk = i, ki = j and ji = -k, kj = -i, ik = -j
+impl<T: Num> Mul for Quaternion<T> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self::Output {
+        let (a, b, c, d) = (self.w, self.x, self.y, self.z);
+        let (e, f, g, h) = (rhs.w, rhs.x, rhs.y, rhs.z);
+        let w = a.clone() * e.clone()
+            - b.clone() * f.clone()
+            - c.clone() * g.clone()
+            - d.clone() * h.clone();
+        let x = a.clone() * f.clone() + b.clone() * e.clone() + c.clone() * h.clone()
+            - d.clone() * g.clone();
+        let y = a.clone() * g.clone() - b.clone() * h.clone()
+            + c.clone() * e.clone()
+            + d.clone() * f.clone();
+        let z = a * h + b * g - c * f + d * e;
+        Self::new(w, x, y, z)
+    }
+}
+
+// right division q / p = q * p^-1
+// Note: for integer scalars the inverse rounds to zero, use
+// Quaternion<f64> or Quaternion<Rational> for exact results
+impl<T: Num> Div for Quaternion<T> {
+    type Output = Self;
+    fn div(self, rhs: Self) -> Self::Output {
+        let n = rhs.norm_sqr();
+        (self * rhs.conjugate()) / n
+    }
+}
+
+impl<T: Num + Neg<Output = T>> Neg for Quaternion<T> {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Self::new(-self.w, -self.x, -self.y, -self.z)
+    }
+}
+
+// scaling by a real number
+impl<T: Num> Mul<T> for Quaternion<T> {
+    type Output = Self;
+    fn mul(self, rhs: T) -> Self::Output {
+        Self::new(
+            self.w * rhs.clone(),
+            self.x * rhs.clone(),
+            self.y * rhs.clone(),
+            self.z * rhs,
+        )
+    }
+}
+
+impl<T: Num> Div<T> for Quaternion<T> {
+    type Output = Self;
+    fn div(self, rhs: T) -> Self::Output {
+        Self::new(
+            self.w / rhs.clone(),
+            self.x / rhs.clone(),
+            self.y / rhs.clone(),
+            self.z / rhs,
+        )
+    }
+}
+
+// assignment operators q op= p written in terms of q = q op p
+// the old value is moved out with mem::replace so nothing is cloned
+macro_rules! impl_assign_op {
+    ($($imp:ident, $method:ident, $op:ident, $op_method:ident;)*) => {
+        $(
+            impl<T: Num> $imp for Quaternion<T> {
+                fn $method(&mut self, rhs: Self) {
+                    let zero = Quaternion::from_vector([T::zero(), T::zero(), T::zero()]);
+                    let q = std::mem::replace(self, zero);
+                    *self = $op::$op_method(q, rhs);
+                }
+            }
+        )*
+    };
+}
+
+impl_assign_op! {
+    AddAssign, add_assign, Add, add;
+    SubAssign, sub_assign, Sub, sub;
+    MulAssign, mul_assign, Mul, mul;
+    DivAssign, div_assign, Div, div;
+}
+
+// same flags as Complex: precision, sign, width and alignment
+impl<T: Num> Display for Quaternion<T> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let zero = T::zero();
+        let terms = [
+            (self.w != zero, format_part(f, &self.w), ""),
+            (self.x != zero, format_part(f, &self.x), "i"),
+            (self.y != zero, format_part(f, &self.y), "j"),
+            (self.z != zero, format_part(f, &self.z), "k"),
+        ];
+        write_terms(f, &terms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BigInt, Rational};
+    use std::f64::consts::PI;
+
+    fn close(a: [f64; 3], b: [f64; 3]) -> bool {
+        a.iter().zip(b).all(|(x, y)| (x - y).abs() < 1e-12)
+    }
+
+    // i^2 = j^2 = k^2 = ijk = -1 and ij = -ji
+    #[test]
+    fn test_hamilton_product() {
+        let one = Quaternion::new(1, 0, 0, 0);
+        let i = Quaternion::new(0, 1, 0, 0);
+        let j = Quaternion::new(0, 0, 1, 0);
+        let k = Quaternion::new(0, 0, 0, 1);
+        assert_eq!(-one, i * i);
+        assert_eq!(-one, j * j);
+        assert_eq!(-one, k * k);
+        assert_eq!(-one, i * j * k);
+        assert_eq!(k, i * j);
+        assert_eq!(-k, j * i);
+        assert_eq!(i, j * k);
+        assert_eq!(j, k * i);
+        let p = Quaternion::new(1, 2, 3, 4);
+        let q = Quaternion::new(5, 6, 7, 8);
+        assert_eq!(Quaternion::new(-60, 12, 30, 24), p * q);
+        assert_eq!(Quaternion::new(-60, 20, 14, 32), q * p);
+    }
+
+    #[test]
+    fn test_conjugate_norm() {
+        let q = Quaternion::new(1, 2, 3, 4);
+        assert_eq!(Quaternion::new(1, -2, -3, -4), q.conjugate());
+        assert_eq!(30, q.norm_sqr());
+        assert_eq!(Quaternion::new(30, 0, 0, 0), q * q.conjugate());
+        assert_eq!(11.0, Quaternion::new(1.0, 2.0, 4.0, 10.0).norm());
+    }
+
+    // exact inverse with rational parts
+    #[test]
+    fn test_inverse() {
+        let r = |n: i64| Rational::from(BigInt::from(n));
+        let q = Quaternion::new(r(1), r(2), r(3), r(4));
+        let inv = q.inverse();
+        assert_eq!("1/30 - 1/15i - 1/10j - 2/15k", inv.to_string());
+        assert_eq!(Quaternion::new(r(1), r(0), r(0), r(0)), q.clone() * inv);
+        let p = Quaternion::new(r(5), r(-6), r(7), r(0));
+        assert_eq!(p, (p.clone() * q.clone()) / q);
+    }
+
+    // quarter turn around z maps x to y
+    #[test]
+    fn test_rotate() {
+        let q = Quaternion::from_axis_angle([0.0, 0.0, 2.0], PI / 2.0);
+        assert!((q.norm() - 1.0).abs() < 1e-15);
+        assert!(close([0.0, 1.0, 0.0], q.rotate([1.0, 0.0, 0.0])));
+        assert!(close([0.0, 0.0, 5.0], q.rotate([0.0, 0.0, 5.0])));
+        // composition applies the right hand rotation first
+        let p = Quaternion::from_axis_angle([1.0, 0.0, 0.0], PI / 2.0);
+        assert!(close([0.0, 0.0, 1.0], (p * q).rotate([1.0, 0.0, 0.0])));
+        // scaling the quaternion does not change the rotation
+        assert!(close(
+            q.rotate([1.0, 2.0, 3.0]),
+            (q * 3.0).rotate([1.0, 2.0, 3.0])
+        ));
+    }
+
+    #[test]
+    fn test_slerp() {
+        let a = Quaternion::from_axis_angle([0.0, 1.0, 0.0], 0.0);
+        let b = Quaternion::from_axis_angle([0.0, 1.0, 0.0], PI / 2.0);
+        let mid = a.slerp(&b, 0.5);
+        let expected = Quaternion::from_axis_angle([0.0, 1.0, 0.0], PI / 4.0);
+        assert!((mid - expected).norm() < 1e-12);
+        assert!((a.slerp(&b, 0.0) - a).norm() < 1e-12);
+        assert!((a.slerp(&b, 1.0) - b).norm() < 1e-12);
+        // -b is the same rotation, the short arc is taken
+        assert!((a.slerp(&-b, 0.5) - expected).norm() < 1e-12);
+        // identical inputs
+        assert!((a.slerp(&a, 0.3) - a).norm() < 1e-12);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!("1 + 2i - 3j + 4k", Quaternion::new(1, 2, -3, 4).to_string());
+        assert_eq!("-2j", Quaternion::new(0, 0, -2, 0).to_string());
+        assert_eq!("0", Quaternion::new(0, 0, 0, 0).to_string());
+        assert_eq!(
+            "+0.50 + 1.00k",
+            format!("{:+.2}", Quaternion::new(0.5, 0.0, 0.0, 1.0))
+        );
+        let c = Quaternion::from(Complex::new(3, 4));
+        assert_eq!("3 + 4i", c.to_string());
+    }
+
+    #[test]
+    fn test_assign_ops() {
+        let mut q = Quaternion::new(1.0, 2.0, 3.0, 4.0);
+        q += Quaternion::new(1.0, 0.0, 0.0, 0.0);
+        q *= Quaternion::new(0.0, 0.0, 0.0, 1.0);
+        assert_eq!(Quaternion::new(-4.0, 3.0, -2.0, 2.0), q);
+        q /= Quaternion::new(0.0, 0.0, 0.0, 1.0);
+        q -= Quaternion::new(1.0, 0.0, 0.0, 0.0);
+        assert_eq!(Quaternion::new(1.0, 2.0, 3.0, 4.0), q);
+    }
+}
@@ -0,0 +1,278 @@
+// Exact fraction of two BigInt values
+// Complex<Rational> divides without any rounding, unlike Complex<i32>
+// which truncates and Complex<f64> which rounds
+// a Rational is always kept in lowest terms with a positive denominator
+// so every value has exactly one representation and the derived
+// equality and hashing are correct
+
+use crate::{BigInt, Num, ParseNumberError};
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Rational {
+    num: BigInt,
+    den: BigInt,
+}
+
+impl Rational {
+    // fraction num / den reduced to lowest terms
+    // Note: panics if den is zero
+    pub fn new(num: BigInt, den: BigInt) -> Self {
+        assert!(!den.is_zero(), "denominator is zero");
+        let g = num.gcd(&den);
+        let (mut num, mut den) = (num / g.clone(), den / g);
+        if den.is_negative() {
+            num = -num;
+            den = -den;
+        }
+        Self { num, den }
+    }
+
+    pub fn numer(&self) -> &BigInt {
+        &self.num
+    }
+
+    pub fn denom(&self) -> &BigInt {
+        &self.den
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.den == BigInt::one()
+    }
+
+    // 1 / self
+    // Note: panics if self is zero
+    pub fn recip(&self) -> Self {
+        Self::new(self.den.clone(), self.num.clone())
+    }
+}
+
+// every integer is a fraction with denominator 1
+macro_rules! impl_from {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Rational {
+                fn from(x: $t) -> Self {
+                    Self {
+                        num: BigInt::from(x),
+                        den: BigInt::one(),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_from!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, BigInt);
+
+impl Ord for Rational {
+    // a/b < c/d <=> ad < cb because both denominators are positive
+    fn cmp(&self, other: &Self) -> Ordering {
+        let lhs = self.num.clone() * other.den.clone();
+        let rhs = other.num.clone() * self.den.clone();
+        lhs.cmp(&rhs)
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add for Rational {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        // a/b + c/d = (ad + cb) / bd
+        let num = self.num * rhs.den.clone() + rhs.num * self.den.clone();
+        Self::new(num, self.den * rhs.den)
+    }
+}
+
+impl Sub for Rational {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl Mul for Rational {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self::new(self.num * rhs.num, self.den * rhs.den)
+    }
+}
+
+// Note: panics when dividing by zero
+impl Div for Rational {
+    type Output = Self;
+    fn div(self, rhs: Self) -> Self::Output {
+        assert!(!rhs.num.is_zero(), "attempt to divide by zero");
+        Self::new(self.num * rhs.den, self.den * rhs.num)
+    }
+}
+
+impl Neg for Rational {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Self {
+            num: -self.num,
+            den: self.den,
+        }
+    }
+}
+
+impl Num for Rational {
+    fn zero() -> Self {
+        Self::from(0)
+    }
+    fn one() -> Self {
+        Self::from(1)
+    }
+    // NaN when both parts are too large for f64
+    fn to_f64(self) -> f64 {
+        self.num.to_f64() / self.den.to_f64()
+    }
+    // drop the fractional part, rounding towards zero
+    fn trunc(self) -> Self {
+        Self::from(self.num / self.den)
+    }
+}
+
+// "n/d", or just "n" for integers
+// width, fill, alignment and the sign flag apply to the whole fraction
+impl Display for Rational {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let n = self.num.abs();
+        let s = if self.is_integer() {
+            n.to_string()
+        } else {
+            format!("{}/{}", n, self.den)
+        };
+        f.pad_integral(!self.num.is_negative(), "", &s)
+    }
+}
+
+// accepts "n", "n/d" and decimals like "-2.25"
+impl FromStr for Rational {
+    type Err = ParseNumberError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((n, d)) = s.split_once('/') {
+            let den: BigInt = d.parse()?;
+            if den.is_zero() {
+                return Err(ParseNumberError::ZeroDenominator);
+            }
+            return Ok(Self::new(n.parse()?, den));
+        }
+        match s.split_once('.') {
+            // the fraction digits must not carry a sign of their own
+            Some((int, frac)) if !frac.starts_with(['+', '-']) => {
+                let digits = format!("{int}{frac}");
+                let den = BigInt::from(10).pow(frac.len() as u32);
+                Ok(Self::new(digits.parse()?, den))
+            }
+            Some(_) => Err(ParseNumberError::InvalidDigit),
+            None => Ok(Self::from(s.parse::<BigInt>()?)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Complex;
+
+    fn q(n: i64, d: i64) -> Rational {
+        Rational::new(BigInt::from(n), BigInt::from(d))
+    }
+
+    // always stored in lowest terms with a positive denominator
+    #[test]
+    fn test_normalize() {
+        let r = q(6, -8);
+        assert_eq!(&BigInt::from(-3), r.numer());
+        assert_eq!(&BigInt::from(4), r.denom());
+        assert_eq!(q(0, 1), q(0, -5));
+        assert!(q(4, 2).is_integer());
+    }
+
+    // Note: panics like division by zero
+    #[test]
+    #[should_panic(expected = "denominator is zero")]
+    fn test_zero_denominator() {
+        q(1, 0);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!(q(5, 6), q(1, 2) + q(1, 3));
+        assert_eq!(q(1, 6), q(1, 2) - q(1, 3));
+        assert_eq!(q(1, 6), q(1, 2) * q(1, 3));
+        assert_eq!(q(3, 2), q(1, 2) / q(1, 3));
+        assert_eq!(q(-2, 1), q(-7, 3).trunc());
+        assert_eq!(q(3, 1), q(1, 3).recip());
+        assert!(q(-1, 2) < q(-1, 3));
+        assert!(q(2, 3) > q(3, 5));
+        assert_eq!(0.75, q(3, 4).to_f64());
+    }
+
+    #[test]
+    fn test_display_parse() {
+        assert_eq!("-3/4", q(-3, 4).to_string());
+        assert_eq!("5", q(10, 2).to_string());
+        assert_eq!("  +1/2", format!("{:+6}", q(1, 2)));
+        assert_eq!(Ok(q(-3, 4)), "-6/8".parse());
+        assert_eq!(Ok(q(-9, 4)), "-2.25".parse());
+        assert_eq!(Ok(q(7, 1)), "7".parse());
+        assert_eq!(
+            Err(ParseNumberError::ZeroDenominator),
+            "1/0".parse::<Rational>()
+        );
+        assert_eq!(Err(ParseNumberError::Empty), "1/-".parse::<Rational>());
+        assert_eq!(
+            Err(ParseNumberError::InvalidDigit),
+            "1.-5".parse::<Rational>()
+        );
+        assert_eq!(Err(ParseNumberError::Empty), "".parse::<Rational>());
+    }
+
+    // division is exact where Complex<i32> truncates
+    #[test]
+    fn test_complex_division() {
+        let a = Complex::new(Rational::from(1), Rational::from(2));
+        let b = Complex::new(Rational::from(3), Rational::from(4));
+        let c = a.clone() / b.clone();
+        assert_eq!(Complex::new(q(11, 25), q(2, 25)), c);
+        assert_eq!(a, c * b);
+        assert_eq!(Complex::new(0, 0), Complex::new(1, 2) / Complex::new(3, 4));
+    }
+
+    // Complex<Rational> shares Display and parsing with the other scalars
+    #[test]
+    fn test_complex_display_parse() {
+        let z = Complex::new(q(1, 2), q(-3, 4));
+        assert_eq!("1/2 - 3/4i", z.to_string());
+        assert_eq!(Ok(z.clone()), "1/2 - 3/4i".parse());
+        assert_eq!(Ok(z), "0.5-0.75j".parse());
+        assert_eq!("2i", Complex::new(q(0, 1), q(4, 2)).to_string());
+    }
+
+    // powers of a complex fraction stay exact
+    #[test]
+    fn test_exact_sum() {
+        let z = Complex::new(q(1, 3), q(1, 7));
+        let mut power = Complex::new(Rational::one(), Rational::zero());
+        let mut sum = Complex::new(Rational::zero(), Rational::zero());
+        for _ in 0..30 {
+            sum += power.clone();
+            power *= z.clone();
+        }
+        // geometric series: sum = (1 - z^30) / (1 - z)
+        let one = Complex::new(Rational::one(), Rational::zero());
+        assert_eq!(sum, (one.clone() - power) / (one - z));
+    }
+}
@@ -0,0 +1,151 @@
+// Serde support for Complex, enabled with the serde feature
+// Complex serializes as a struct {"re": .., "im": ..} by default
+// the compact module serializes as a tuple [re, im] instead, select it per field:
+//   #[derive(Serialize, Deserialize)]
+//   struct Sample {
+//       #[serde(with = "complex_num::serialize::compact")]
+//       value: Complex<f32>,
+//   }
+// deserializing accepts both forms so data written either way can be read back
+
+use crate::Complex;
+use serde::de::{self, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{SerializeStruct, SerializeTuple, Serializer};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Formatter, Result as FmtResult};
+use std::marker::PhantomData;
+
+const FIELDS: &[&str] = &["re", "im"];
+
+impl<T: Serialize> Serialize for Complex<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("Complex", 2)?;
+        s.serialize_field("re", &self.real)?;
+        s.serialize_field("im", &self.imag)?;
+        s.end()
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Complex<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_struct("Complex", FIELDS, ComplexVisitor(PhantomData))
+    }
+}
+
+struct ComplexVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Deserialize<'de>> Visitor<'de> for ComplexVisitor<T> {
+    type Value = Complex<T>;
+
+    fn expecting(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "a complex number as {{\"re\", \"im\"}} or [re, im]")
+    }
+
+    // [re, im]
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let real = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let imag = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        if seq.next_element::<de::IgnoredAny>()?.is_some() {
+            return Err(de::Error::invalid_length(3, &self));
+        }
+        Ok(Complex { real, imag })
+    }
+
+    // {"re": .., "im": ..} in any order
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let (mut real, mut imag) = (None, None);
+        while let Some(key) = map.next_key::<String>()? {
+            let slot = match key.as_str() {
+                "re" => &mut real,
+                "im" => &mut imag,
+                _ => return Err(de::Error::unknown_field(&key, FIELDS)),
+            };
+            if slot.is_some() {
+                return Err(de::Error::custom(format!("duplicate field `{key}`")));
+            }
+            *slot = Some(map.next_value()?);
+        }
+        Ok(Complex {
+            real: real.ok_or_else(|| de::Error::missing_field("re"))?,
+            imag: imag.ok_or_else(|| de::Error::missing_field("im"))?,
+        })
+    }
+}
+
+// [re, im] representation for #[serde(with = "...")]
+pub mod compact {
+    use super::*;
+
+    pub fn serialize<T: Serialize, S: Serializer>(
+        value: &Complex<T>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_tuple(2)?;
+        s.serialize_element(&value.real)?;
+        s.serialize_element(&value.imag)?;
+        s.end()
+    }
+
+    pub fn deserialize<'de, T: Deserialize<'de>, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Complex<T>, D::Error> {
+        deserializer.deserialize_tuple(2, ComplexVisitor(PhantomData))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the struct form is the default
+    #[test]
+    fn test_struct_form() {
+        let n = Complex::new(1.5f64, -2.0);
+        let json = serde_json::to_string(&n).unwrap();
+        assert_eq!(r#"{"re":1.5,"im":-2.0}"#, json);
+        assert_eq!(n, serde_json::from_str(&json).unwrap());
+        let n: Complex<i32> = serde_json::from_str(r#"{"im": 4, "re": 3}"#).unwrap();
+        assert_eq!(Complex::new(3, 4), n);
+    }
+
+    // the compact form, selected per field
+    #[test]
+    fn test_compact_form() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Sample {
+            #[serde(with = "compact")]
+            value: Complex<f32>,
+        }
+
+        let s = Sample {
+            value: Complex::new(0.5, 1.0),
+        };
+        let json = serde_json::to_string(&s).unwrap();
+        assert_eq!(r#"{"value":[0.5,1.0]}"#, json);
+        assert_eq!(s, serde_json::from_str(&json).unwrap());
+    }
+
+    // either form can be read back
+    #[test]
+    fn test_deserialize_any_form() {
+        let n: Complex<i32> = serde_json::from_str("[3, 4]").unwrap();
+        assert_eq!(Complex::new(3, 4), n);
+        let v: Vec<Complex<i32>> = serde_json::from_str(r#"[[1, 2], {"re": 3, "im": 4}]"#).unwrap();
+        assert_eq!(vec![Complex::new(1, 2), Complex::new(3, 4)], v);
+    }
+
+    // malformed input is rejected
+    #[test]
+    fn test_deserialize_errors() {
+        assert!(serde_json::from_str::<Complex<i32>>("[1]").is_err());
+        assert!(serde_json::from_str::<Complex<i32>>("[1, 2, 3]").is_err());
+        assert!(serde_json::from_str::<Complex<i32>>(r#"{"re": 1}"#).is_err());
+        assert!(serde_json::from_str::<Complex<i32>>(r#"{"re": 1, "im": 2, "x": 3}"#).is_err());
+        assert!(serde_json::from_str::<Complex<i32>>(r#"{"re": 1, "re": 2}"#).is_err());
+        assert!(serde_json::from_str::<Complex<u8>>("[-1, 0]").is_err());
+    }
+}
@@ -0,0 +1,77 @@
+// ArenaList against the enum based LinkedList
+//   cargo bench -p linked_list --bench list
+
+use criterion::{
+    black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput,
+};
+use linked_list::{ArenaList, LinkedList};
+
+const SIZES: [usize; 2] = [1024, 65536];
+
+fn bench_push(c: &mut Criterion) {
+    let mut group = c.benchmark_group("push");
+    for n in SIZES {
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(BenchmarkId::new("enum", n), &n, |bench, &n| {
+            bench.iter(|| {
+                let mut list = LinkedList::new();
+                for i in 0..n {
+                    list.push_front(black_box(i));
+                }
+                list
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("arena", n), &n, |bench, &n| {
+            bench.iter(|| {
+                let mut list = ArenaList::new();
+                for i in 0..n {
+                    list.push_front(black_box(i));
+                }
+                list
+            })
+        });
+    }
+    group.finish();
+}
+
+// the lists are built outside of the timed part
+fn bench_pop(c: &mut Criterion) {
+    let mut group = c.benchmark_group("pop");
+    for n in SIZES {
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(BenchmarkId::new("enum", n), &n, |bench, &n| {
+            bench.iter_batched(
+                || (0..n).collect::<LinkedList<_>>(),
+                |mut list| while black_box(list.pop_front()).is_some() {},
+                BatchSize::LargeInput,
+            )
+        });
+        group.bench_with_input(BenchmarkId::new("arena", n), &n, |bench, &n| {
+            bench.iter_batched(
+                || (0..n).collect::<ArenaList<_>>(),
+                |mut list| while black_box(list.pop_front()).is_some() {},
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn bench_iterate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("iterate");
+    for n in SIZES {
+        let linked: LinkedList<usize> = (0..n).collect();
+        let arena: ArenaList<usize> = (0..n).collect();
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(BenchmarkId::new("enum", n), &n, |bench, _| {
+            bench.iter(|| black_box(&linked).iter().sum::<usize>())
+        });
+        group.bench_with_input(BenchmarkId::new("arena", n), &n, |bench, _| {
+            bench.iter(|| black_box(&arena).iter().sum::<usize>())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_push, bench_pop, bench_iterate);
+criterion_main!(benches);
@@ -0,0 +1,478 @@
+// Arena backed linked list
+// LinkedList and DoublyLinkedList allocate every node on its own. Here
+// all nodes live in one Vec (the arena) and link to each other by their
+// u32 index in it, so pushing mostly writes into memory that is already
+// there and walking the list stays within one allocation
+// - push_front, push_back, pop_front and pop_back in O(1)
+// - every push returns a Handle to the element, get, get_mut, remove,
+// insert_before and insert_after with a handle are O(1)
+// - removed slots go on a free list and are reused by the next push,
+// the arena only grows when the free list is empty
+// - a handle stays valid until its element is removed, a handle to a
+// removed element returns None even after its slot was reused
+// - iter from both ends, collect, extend and display as 1 <-> 2 <-> 3
+//
+// every slot carries a generation that goes up when its element is
+// removed, a Handle stores the index and the generation it was made for
+// a slot whose generation would wrap around is retired and never reused,
+// so an old handle cannot match it again
+
+use std::fmt::{self, Debug, Display, Formatter};
+use std::iter::FusedIterator;
+
+// index used for "no slot": end of the list or of the free list
+const NIL: u32 = u32::MAX;
+
+// stable reference to an element of an ArenaList
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Handle {
+    index: u32,
+    generation: u32,
+}
+
+// a free slot has no value and next links to the next free slot
+struct Slot<T> {
+    val: Option<T>,
+    prev: u32,
+    next: u32,
+    generation: u32,
+}
+
+pub struct ArenaList<T> {
+    slots: Vec<Slot<T>>,
+    head: u32,
+    tail: u32,
+    free: u32,
+    len: usize,
+}
+
+impl<T> ArenaList<T> {
+    // create a new empty list
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    // room for capacity elements before the arena has to grow
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            slots: Vec::with_capacity(capacity),
+            head: NIL,
+            tail: NIL,
+            free: NIL,
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn push_front(&mut self, val: T) -> Handle {
+        self.link(val, NIL, self.head)
+    }
+
+    pub fn push_back(&mut self, val: T) -> Handle {
+        self.link(val, self.tail, NIL)
+    }
+
+    // returns None if the list is empty
+    pub fn pop_front(&mut self) -> Option<T> {
+        (self.head != NIL).then(|| self.unlink(self.head))
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        (self.tail != NIL).then(|| self.unlink(self.tail))
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.value(self.head)
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        self.value(self.tail)
+    }
+
+    // true while the element of the handle is in the list
+    pub fn contains(&self, handle: Handle) -> bool {
+        self.slots
+            .get(handle.index as usize)
+            .is_some_and(|s| s.generation == handle.generation && s.val.is_some())
+    }
+
+    // None if the element was removed
+    pub fn get(&self, handle: Handle) -> Option<&T> {
+        if !self.contains(handle) {
+            return None;
+        }
+        self.value(handle.index)
+    }
+
+    pub fn get_mut(&mut self, handle: Handle) -> Option<&mut T> {
+        if !self.contains(handle) {
+            return None;
+        }
+        self.slots[handle.index as usize].val.as_mut()
+    }
+
+    // remove the element of the handle wherever it is in the list
+    // None if it was already removed
+    pub fn remove(&mut self, handle: Handle) -> Option<T> {
+        self.contains(handle).then(|| self.unlink(handle.index))
+    }
+
+    // insert next to the element of the handle, None if it was removed
+    pub fn insert_before(&mut self, handle: Handle, val: T) -> Option<Handle> {
+        let prev = self.slots.get(handle.index as usize)?.prev;
+        self.contains(handle)
+            .then(|| self.link(val, prev, handle.index))
+    }
+
+    pub fn insert_after(&mut self, handle: Handle, val: T) -> Option<Handle> {
+        let next = self.slots.get(handle.index as usize)?.next;
+        self.contains(handle)
+            .then(|| self.link(val, handle.index, next))
+    }
+
+    // remove all elements, the slots stay allocated for reuse
+    pub fn clear(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            list: self,
+            front: self.head,
+            back: self.tail,
+            len: self.len,
+        }
+    }
+
+    fn value(&self, index: u32) -> Option<&T> {
+        self.slots.get(index as usize)?.val.as_ref()
+    }
+
+    // put val in a free slot between prev and next
+    // Note: panics if the arena would need more than u32::MAX slots
+    fn link(&mut self, val: T, prev: u32, next: u32) -> Handle {
+        let index = if self.free != NIL {
+            let index = self.free;
+            let slot = &mut self.slots[index as usize];
+            self.free = slot.next;
+            slot.val = Some(val);
+            slot.prev = prev;
+            slot.next = next;
+            index
+        } else {
+            let index = u32::try_from(self.slots.len())
+                .ok()
+                .filter(|&i| i != NIL)
+                .expect("arena list is full");
+            self.slots.push(Slot {
+                val: Some(val),
+                prev,
+                next,
+                generation: 0,
+            });
+            index
+        };
+        match prev {
+            NIL => self.head = index,
+            p => self.slots[p as usize].next = index,
+        }
+        match next {
+            NIL => self.tail = index,
+            n => self.slots[n as usize].prev = index,
+        }
+        self.len += 1;
+        Handle {
+            index,
+            generation: self.slots[index as usize].generation,
+        }
+    }
+
+    // take the value out of an occupied slot and put the slot on the
+    // free list, its generation goes up so old handles stop matching
+    fn unlink(&mut self, index: u32) -> T {
+        let slot = &mut self.slots[index as usize];
+        let val = slot.val.take().expect("unlink an occupied slot");
+        let (prev, next) = (slot.prev, slot.next);
+        if let Some(generation) = slot.generation.checked_add(1) {
+            slot.generation = generation;
+            slot.next = self.free;
+            self.free = index;
+        }
+        match prev {
+            NIL => self.head = next,
+            p => self.slots[p as usize].next = next,
+        }
+        match next {
+            NIL => self.tail = prev,
+            n => self.slots[n as usize].prev = prev,
+        }
+        self.len -= 1;
+        val
+    }
+}
+
+// iterator over the elements from head to tail, or back to front
+pub struct Iter<'a, T> {
+    list: &'a ArenaList<T>,
+    front: u32,
+    back: u32,
+    // elements left, so both ends stop when they meet
+    len: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let slot = &self.list.slots[self.front as usize];
+        self.front = slot.next;
+        self.len -= 1;
+        slot.val.as_ref()
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let slot = &self.list.slots[self.back as usize];
+        self.back = slot.prev;
+        self.len -= 1;
+        slot.val.as_ref()
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+impl<T> FusedIterator for Iter<'_, T> {}
+
+impl<'a, T> IntoIterator for &'a ArenaList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T> Default for ArenaList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> FromIterator<T> for ArenaList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+// appends at the back
+impl<T> Extend<T> for ArenaList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for v in iter {
+            self.push_back(v);
+        }
+    }
+}
+
+impl<T: Display> Display for ArenaList<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for (i, v) in self.iter().enumerate() {
+            if i > 0 {
+                write!(f, " <-> ")?;
+            }
+            write!(f, "{v}")?;
+        }
+        Ok(())
+    }
+}
+
+// prints like a Vec, [1, 2, 3]
+impl<T: Debug> Debug for ArenaList<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::VecDeque;
+
+    // cleared lists keep their slots, handles into them go stale
+    #[test]
+    fn test_clear() {
+        let mut list: ArenaList<i32> = ArenaList::with_capacity(8);
+        assert!(list.is_empty());
+        assert!(list.slots.capacity() >= 8);
+        let h = list.push_back(1);
+        list.extend(2..=4);
+        list.clear();
+        assert!(list.is_empty());
+        assert_eq!(None, list.pop_back());
+        assert_eq!(None, list.get(h));
+        assert_eq!("[]", format!("{list:?}"));
+        assert_eq!(4, list.slots.len());
+    }
+
+    // random pushes, pops, inserts and removes by handle against a
+    // VecDeque of (value, handle), the arena only grows when the list
+    // is longer than it ever was, so every freed slot is reused
+    #[test]
+    fn test_random_handles() {
+        let mut list = ArenaList::new();
+        let mut oracle: VecDeque<(usize, Handle)> = VecDeque::new();
+        let mut removed = vec![];
+        let mut longest = 0;
+        let mut x: u64 = 5;
+        for i in 0..5000 {
+            x = x
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let r = (x >> 33) as usize;
+            let at = (r / 8) % oracle.len().max(1);
+            match r % 8 {
+                0 | 1 => oracle.push_front((i, list.push_front(i))),
+                2 => oracle.push_back((i, list.push_back(i))),
+                3 => {
+                    let popped = oracle.pop_front();
+                    assert_eq!(popped.map(|e| e.0), list.pop_front());
+                    removed.extend(popped.map(|e| e.1));
+                }
+                4 => {
+                    let popped = oracle.pop_back();
+                    assert_eq!(popped.map(|e| e.0), list.pop_back());
+                    removed.extend(popped.map(|e| e.1));
+                }
+                5 | 6 if !oracle.is_empty() => {
+                    let (v, h) = oracle.remove(at).unwrap();
+                    assert_eq!(Some(v), list.remove(h));
+                    removed.push(h);
+                }
+                _ if !oracle.is_empty() => {
+                    let h = oracle[at].1;
+                    if (r >> 16) & 1 == 0 {
+                        oracle.insert(at, (i, list.insert_before(h, i).unwrap()));
+                    } else {
+                        oracle.insert(at + 1, (i, list.insert_after(h, i).unwrap()));
+                    }
+                }
+                _ => {}
+            }
+            longest = longest.max(oracle.len());
+            assert_eq!(oracle.len(), list.len());
+            assert_eq!(longest, list.slots.len());
+        }
+        assert!(oracle.iter().map(|e| &e.0).eq(list.iter()));
+        assert!(oracle.iter().rev().map(|e| &e.0).eq(list.iter().rev()));
+        for (v, h) in &oracle {
+            assert_eq!(Some(v), list.get(*h));
+        }
+        assert!(removed.iter().all(|h| !list.contains(*h)));
+    }
+
+    // handles reach their element wherever it is, in O(1)
+    #[test]
+    fn test_handles() {
+        let mut list = ArenaList::new();
+        let b = list.push_back("b");
+        let d = list.push_back("d");
+        let a = list.push_front("a");
+        let c = list.insert_after(b, "c").unwrap();
+        list.insert_before(a, "_").unwrap();
+        assert_eq!("_ <-> a <-> b <-> c <-> d", list.to_string());
+        *list.get_mut(c).unwrap() = "C";
+        assert_eq!(Some("C"), list.remove(c));
+        assert_eq!(Some("a"), list.remove(a));
+        assert_eq!(Some(&"d"), list.get(d));
+        assert_eq!(Some("d"), list.pop_back());
+        assert_eq!("_ <-> b", list.to_string());
+        assert_eq!(Some(&"b"), list.back());
+    }
+
+    // removed elements leave stale handles, even once the slot is reused
+    #[test]
+    fn test_stale_handles() {
+        let mut list: ArenaList<i32> = (0..3).collect();
+        let h = list.push_back(3);
+        assert_eq!(Some(3), list.remove(h));
+        assert!(!list.contains(h));
+        assert_eq!(None, list.remove(h));
+        assert_eq!(None, list.get(h));
+        assert_eq!(None, list.insert_after(h, 9));
+        let reused = list.push_front(4);
+        assert_eq!(h.index, reused.index);
+        assert_eq!(None, list.get_mut(h));
+        assert_eq!(Some(&4), list.get(reused));
+        assert_eq!("4 <-> 0 <-> 1 <-> 2", list.to_string());
+    }
+
+    // a slot at the last generation is retired instead of reused
+    #[test]
+    fn test_generation_limit() {
+        let mut list = ArenaList::new();
+        let h = list.push_back(1);
+        list.slots[h.index as usize].generation = u32::MAX;
+        let last = Handle {
+            index: h.index,
+            generation: u32::MAX,
+        };
+        assert_eq!(Some(1), list.remove(last));
+        let next = list.push_back(2);
+        assert_ne!(last.index, next.index);
+        assert_eq!(None, list.get(last));
+        assert_eq!(None, list.insert_before(last, 3));
+        assert_eq!(2, list.slots.len());
+        assert_eq!("2", list.to_string());
+    }
+
+    // the arena does not grow while freed slots are left
+    #[test]
+    fn test_free_list_reuse() {
+        let mut list: ArenaList<usize> = (0..100).collect();
+        for _ in 0..10 {
+            list.clear();
+            list.extend(0..100);
+        }
+        assert_eq!(100, list.slots.len());
+        let handles: Vec<Handle> = (0..50).map(|i| list.push_back(i)).collect();
+        for h in handles.iter().step_by(2) {
+            list.remove(*h);
+        }
+        for i in 0..25 {
+            list.push_front(i);
+        }
+        assert_eq!(150, list.slots.len());
+        assert_eq!(150, list.len());
+    }
+
+    // both ends of the iterator meet in the middle
+    #[test]
+    fn test_iter() {
+        let list: ArenaList<i32> = (1..=5).collect();
+        let mut it = list.iter();
+        assert_eq!(Some(&1), it.next());
+        assert_eq!(Some(&5), it.next_back());
+        assert_eq!(3, it.len());
+        assert_eq!(vec![&2, &3, &4], it.collect::<Vec<_>>());
+        let mut total = 0;
+        for v in &list {
+            total += v;
+        }
+        assert_eq!(15, total);
+    }
+}
@@ -0,0 +1,603 @@
+// Doubly linked list
+// the enum based LinkedList only knows its next element, so anything
+// at the end of the list means walking it. Here every node also links
+// back to the previous one and the list keeps both ends
+// - push_front, pop_front, push_back and pop_back in O(1)
+// - front and back (and their _mut versions) to look at the ends
+// - a cursor that walks in both directions and edits where it points:
+// insert_before, insert_after and remove_current in O(1)
+// - append in O(1), split_off and splice walk from the nearer end
+// - display the list as 1 <-> 2 <-> 3
+//
+// ownership: next links and the head are strong Rc pointers, prev links
+// are Weak so there is no reference cycle and dropping the list frees
+// every node. The tail is a second strong pointer to the last node.
+// RefCell moves the borrow checks to run time, every borrow below
+// ends before the next one starts so none of them can panic
+
+use std::cell::{Ref, RefCell, RefMut};
+use std::fmt::{self, Debug, Display, Formatter};
+use std::iter;
+use std::mem;
+use std::rc::{Rc, Weak};
+
+type Link<T> = Option<Rc<RefCell<Node<T>>>>;
+
+struct Node<T> {
+    val: T,
+    next: Link<T>,
+    prev: Option<Weak<RefCell<Node<T>>>>,
+}
+
+pub struct DoublyLinkedList<T> {
+    head: Link<T>,
+    tail: Link<T>,
+    len: usize,
+}
+
+impl<T> DoublyLinkedList<T> {
+    // create a new empty list
+    pub fn new() -> Self {
+        Self {
+            head: None,
+            tail: None,
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn push_front(&mut self, val: T) {
+        self.link_after(None, val);
+    }
+
+    pub fn push_back(&mut self, val: T) {
+        self.link_after(self.tail.clone(), val);
+    }
+
+    // returns None if the list is empty
+    pub fn pop_front(&mut self) -> Option<T> {
+        let node = self.head.clone()?;
+        Some(self.unlink(node))
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        let node = self.tail.clone()?;
+        Some(self.unlink(node))
+    }
+
+    // the elements live inside a RefCell, so they are handed out
+    // as Ref / RefMut guards instead of plain references
+    pub fn front(&self) -> Option<Ref<'_, T>> {
+        self.head.as_ref().map(|n| Ref::map(n.borrow(), |n| &n.val))
+    }
+
+    pub fn back(&self) -> Option<Ref<'_, T>> {
+        self.tail.as_ref().map(|n| Ref::map(n.borrow(), |n| &n.val))
+    }
+
+    pub fn front_mut(&mut self) -> Option<RefMut<'_, T>> {
+        self.head
+            .as_ref()
+            .map(|n| RefMut::map(n.borrow_mut(), |n| &mut n.val))
+    }
+
+    pub fn back_mut(&mut self) -> Option<RefMut<'_, T>> {
+        self.tail
+            .as_ref()
+            .map(|n| RefMut::map(n.borrow_mut(), |n| &mut n.val))
+    }
+
+    // remove all elements
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    // move all elements of other to the end of self, other becomes empty
+    pub fn append(&mut self, other: &mut Self) {
+        let Some(tail) = self.tail.take() else {
+            mem::swap(self, other);
+            return;
+        };
+        match other.head.take() {
+            Some(head) => {
+                head.borrow_mut().prev = Some(Rc::downgrade(&tail));
+                tail.borrow_mut().next = Some(head);
+                self.tail = other.tail.take();
+                self.len += mem::replace(&mut other.len, 0);
+            }
+            None => self.tail = Some(tail),
+        }
+    }
+
+    // split the list in two at the given index
+    // self keeps [0, at) and the returned list holds [at, len)
+    // Note: panics if at > len() just like std LinkedList::split_off
+    pub fn split_off(&mut self, at: usize) -> Self {
+        assert!(at <= self.len, "cannot split off at a nonexistent index");
+        if at == 0 {
+            return mem::take(self);
+        }
+        let Some(node) = self.node_at(at) else {
+            return Self::new();
+        };
+        let prev = node.borrow_mut().prev.take().and_then(|w| w.upgrade());
+        let prev = prev.expect("a node after the head has a previous node");
+        prev.borrow_mut().next = None;
+        let rest = Self {
+            head: Some(node),
+            tail: self.tail.replace(prev),
+            len: self.len - at,
+        };
+        self.len = at;
+        rest
+    }
+
+    // insert all elements of other so that the first one ends up at
+    // position at, the elements after it move behind the inserted ones
+    // Note: panics if at > len()
+    pub fn splice(&mut self, at: usize, mut other: Self) {
+        let mut rest = self.split_off(at);
+        self.append(&mut other);
+        self.append(&mut rest);
+    }
+
+    // a cursor on the first element, see CursorMut
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            current: self.head.clone(),
+            index: 0,
+            list: self,
+        }
+    }
+
+    // a cursor on the last element
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            current: self.tail.clone(),
+            index: self.len.saturating_sub(1),
+            list: self,
+        }
+    }
+
+    // node at position index, walking from the nearer end
+    fn node_at(&self, index: usize) -> Link<T> {
+        if index < self.len / 2 {
+            self.nodes().nth(index)
+        } else {
+            let back = self.len.checked_sub(index + 1)?;
+            let prev = |n: &Rc<RefCell<Node<T>>>| n.borrow().prev.as_ref()?.upgrade();
+            iter::successors(self.tail.clone(), prev).nth(back)
+        }
+    }
+
+    // put a new node right after prev, or at the head if prev is None
+    fn link_after(&mut self, prev: Link<T>, val: T) {
+        let next = match &prev {
+            Some(p) => p.borrow_mut().next.take(),
+            None => self.head.take(),
+        };
+        let node = Rc::new(RefCell::new(Node {
+            val,
+            next: next.clone(),
+            prev: prev.as_ref().map(Rc::downgrade),
+        }));
+        match next {
+            Some(n) => n.borrow_mut().prev = Some(Rc::downgrade(&node)),
+            None => self.tail = Some(node.clone()),
+        }
+        match prev {
+            Some(p) => p.borrow_mut().next = Some(node),
+            None => self.head = Some(node),
+        }
+        self.len += 1;
+    }
+
+    // take a node out of the list and return its element
+    // Note: the caller passes in its own pointer to the node, once the
+    // neighbours let go of theirs it is the last strong one
+    fn unlink(&mut self, node: Rc<RefCell<Node<T>>>) -> T {
+        let (prev, next) = {
+            let mut n = node.borrow_mut();
+            (n.prev.take().and_then(|w| w.upgrade()), n.next.take())
+        };
+        match &next {
+            Some(n) => n.borrow_mut().prev = prev.as_ref().map(Rc::downgrade),
+            None => self.tail = prev.clone(),
+        }
+        match prev {
+            Some(p) => p.borrow_mut().next = next,
+            None => self.head = next,
+        }
+        self.len -= 1;
+        match Rc::try_unwrap(node) {
+            Ok(node) => node.into_inner().val,
+            Err(_) => unreachable!("an unlinked node has no other owner"),
+        }
+    }
+
+    // every node from head to tail, each step clones one Rc
+    fn nodes(&self) -> impl Iterator<Item = Rc<RefCell<Node<T>>>> {
+        iter::successors(self.head.clone(), |n| n.borrow().next.clone())
+    }
+}
+
+// A cursor points at one element of the list and can move to the
+// next or previous one. Past either end it points at a "ghost"
+// position between the tail and the head, moving on from there wraps
+// around. It holds the list mutably, so the list can only be changed
+// through the cursor while it lives
+pub struct CursorMut<'a, T> {
+    list: &'a mut DoublyLinkedList<T>,
+    current: Link<T>,
+    // position of current, equal to list.len() at the ghost position
+    index: usize,
+}
+
+impl<T> CursorMut<'_, T> {
+    // None at the ghost position
+    pub fn index(&self) -> Option<usize> {
+        self.current.as_ref().map(|_| self.index)
+    }
+
+    pub fn current(&mut self) -> Option<RefMut<'_, T>> {
+        self.current
+            .as_ref()
+            .map(|n| RefMut::map(n.borrow_mut(), |n| &mut n.val))
+    }
+
+    pub fn move_next(&mut self) {
+        match self.current.take() {
+            Some(node) => {
+                self.current = node.borrow().next.clone();
+                self.index += 1;
+            }
+            None => {
+                self.current = self.list.head.clone();
+                self.index = 0;
+            }
+        }
+    }
+
+    pub fn move_prev(&mut self) {
+        match self.current.take() {
+            Some(node) => {
+                self.current = node.borrow().prev.as_ref().and_then(|w| w.upgrade());
+                self.index = match self.current {
+                    Some(_) => self.index - 1,
+                    None => self.list.len,
+                };
+            }
+            None => {
+                self.current = self.list.tail.clone();
+                self.index = self.list.len.saturating_sub(1);
+            }
+        }
+    }
+
+    // insert before the current element
+    // at the ghost position that is the end of the list
+    pub fn insert_before(&mut self, val: T) {
+        match &self.current {
+            Some(node) => {
+                let prev = node.borrow().prev.as_ref().and_then(|w| w.upgrade());
+                self.list.link_after(prev, val);
+            }
+            None => self.list.push_back(val),
+        }
+        self.index += 1;
+    }
+
+    // insert after the current element
+    // at the ghost position that is the start of the list
+    pub fn insert_after(&mut self, val: T) {
+        match &self.current {
+            Some(node) => self.list.link_after(Some(node.clone()), val),
+            None => {
+                self.list.push_front(val);
+                self.index += 1;
+            }
+        }
+    }
+
+    // remove the current element and move on to the next one
+    // returns None at the ghost position
+    pub fn remove_current(&mut self) -> Option<T> {
+        let node = self.current.take()?;
+        self.current = node.borrow().next.clone();
+        Some(self.list.unlink(node))
+    }
+}
+
+impl<T> Default for DoublyLinkedList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// unlink the nodes one by one, dropping the head would otherwise drop
+// each next node inside the previous one and recurse through the list
+impl<T> Drop for DoublyLinkedList<T> {
+    fn drop(&mut self) {
+        self.tail = None;
+        let mut cur = self.head.take();
+        while let Some(node) = cur {
+            cur = node.borrow_mut().next.take();
+        }
+    }
+}
+
+// owning iterator, takes elements from both ends in O(1)
+pub struct IntoIter<T>(DoublyLinkedList<T>);
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop_front()
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for DoublyLinkedList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self)
+    }
+}
+
+impl<T> FromIterator<T> for DoublyLinkedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+// appends at the back
+impl<T> Extend<T> for DoublyLinkedList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for v in iter {
+            self.push_back(v);
+        }
+    }
+}
+
+impl<T: Display> Display for DoublyLinkedList<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for (i, node) in self.nodes().enumerate() {
+            if i > 0 {
+                write!(f, " <-> ")?;
+            }
+            write!(f, "{}", node.borrow().val)?;
+        }
+        Ok(())
+    }
+}
+
+// prints like a Vec, [1, 2, 3]
+impl<T: Debug> Debug for DoublyLinkedList<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let mut list = f.debug_list();
+        for node in self.nodes() {
+            list.entry(&node.borrow().val);
+        }
+        list.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::VecDeque;
+
+    fn to_vec<T: Clone>(list: &DoublyLinkedList<T>) -> Vec<T> {
+        list.nodes().map(|n| n.borrow().val.clone()).collect()
+    }
+
+    #[test]
+    fn test_blank_list() {
+        let mut list: DoublyLinkedList<i32> = DoublyLinkedList::new();
+        assert!(list.is_empty());
+        assert_eq!(None, list.pop_front());
+        assert_eq!(None, list.pop_back());
+        assert!(list.front().is_none());
+        assert!(list.back().is_none());
+        assert_eq!("", list.to_string());
+        assert_eq!("[]", format!("{list:?}"));
+    }
+
+    // push and pop at both ends against VecDeque
+    #[test]
+    fn test_both_ends() {
+        let mut list = DoublyLinkedList::new();
+        let mut oracle = VecDeque::new();
+        for i in 0..200 {
+            match i % 7 {
+                0 | 3 => assert_eq!(oracle.pop_back(), list.pop_back()),
+                1 => assert_eq!(oracle.pop_front(), list.pop_front()),
+                2 | 5 => {
+                    list.push_front(i);
+                    oracle.push_front(i);
+                }
+                _ => {
+                    list.push_back(i);
+                    oracle.push_back(i);
+                }
+            }
+            assert_eq!(oracle.len(), list.len());
+            assert_eq!(oracle.front(), list.front().as_deref());
+            assert_eq!(oracle.back(), list.back().as_deref());
+        }
+        assert_eq!(Vec::from(oracle), to_vec(&list));
+    }
+
+    #[test]
+    fn test_front_back_mut() {
+        let mut list: DoublyLinkedList<i32> = (1..=3).collect();
+        *list.front_mut().unwrap() *= 10;
+        *list.back_mut().unwrap() += 1;
+        assert_eq!("10 <-> 2 <-> 4", list.to_string());
+        assert_eq!("[10, 2, 4]", format!("{list:?}"));
+        list.clear();
+        assert!(list.is_empty());
+    }
+
+    // walk forwards and backwards through the ghost position
+    #[test]
+    fn test_cursor_move() {
+        let mut list: DoublyLinkedList<i32> = (1..=3).collect();
+        let mut cursor = list.cursor_front_mut();
+        assert_eq!(Some(0), cursor.index());
+        assert_eq!(1, *cursor.current().unwrap());
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(Some(2), cursor.index());
+        cursor.move_next();
+        assert_eq!(None, cursor.index());
+        assert!(cursor.current().is_none());
+        cursor.move_next();
+        assert_eq!(1, *cursor.current().unwrap());
+        cursor.move_prev();
+        assert_eq!(None, cursor.index());
+        cursor.move_prev();
+        assert_eq!(Some(2), cursor.index());
+        *cursor.current().unwrap() = 30;
+        assert_eq!("1 <-> 2 <-> 30", list.to_string());
+        let mut empty: DoublyLinkedList<i32> = DoublyLinkedList::new();
+        let mut cursor = empty.cursor_back_mut();
+        cursor.move_prev();
+        assert_eq!(None, cursor.index());
+    }
+
+    #[test]
+    fn test_cursor_edit() {
+        let mut list: DoublyLinkedList<i32> = (1..=3).collect();
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_next();
+        cursor.insert_before(10);
+        cursor.insert_after(20);
+        assert_eq!(Some(2), cursor.index());
+        assert_eq!(Some(2), cursor.remove_current());
+        assert_eq!(20, *cursor.current().unwrap());
+        cursor.move_next();
+        cursor.move_next();
+        // ghost position: before means at the back, after at the front
+        assert_eq!(None, cursor.remove_current());
+        cursor.insert_before(4);
+        cursor.insert_after(0);
+        cursor.move_prev();
+        assert_eq!(Some(5), cursor.index());
+        assert_eq!(Some(4), cursor.remove_current());
+        assert_eq!(None, cursor.index());
+        assert_eq!("0 <-> 1 <-> 10 <-> 20 <-> 3", list.to_string());
+        assert_eq!(3, *list.back().unwrap());
+        let mut cursor = list.cursor_back_mut();
+        assert_eq!(Some(3), cursor.remove_current());
+        cursor.move_prev();
+        assert_eq!(20, *cursor.current().unwrap());
+        assert_eq!("0 <-> 1 <-> 10 <-> 20", list.to_string());
+    }
+
+    // remove elements through the cursor, keeping the one after each
+    // multiple of 3, both ends of the list stay right
+    #[test]
+    fn test_cursor_remove_many() {
+        let mut list: DoublyLinkedList<i32> = (0..11).collect();
+        let mut cursor = list.cursor_front_mut();
+        while let Some(v) = cursor.remove_current() {
+            if v % 3 == 0 {
+                cursor.move_next();
+            }
+        }
+        assert_eq!("1 <-> 4 <-> 7 <-> 10", list.to_string());
+        assert_eq!(1, *list.front().unwrap());
+        assert_eq!(10, *list.back().unwrap());
+        assert_eq!(
+            vec![10, 7, 4, 1],
+            list.into_iter().rev().collect::<Vec<_>>()
+        );
+    }
+
+    // every split point of lists of several lengths
+    #[test]
+    fn test_split_off() {
+        for n in 0..8 {
+            for at in 0..=n {
+                let mut list: DoublyLinkedList<usize> = (0..n).collect();
+                let rest = list.split_off(at);
+                assert_eq!((0..at).collect::<Vec<_>>(), to_vec(&list));
+                assert_eq!((at..n).collect::<Vec<_>>(), to_vec(&rest));
+                assert_eq!(at.checked_sub(1), list.back().map(|v| *v));
+                assert_eq!(n - at, rest.len());
+                assert_eq!(
+                    (at..n).rev().collect::<Vec<_>>(),
+                    rest.into_iter().rev().collect::<Vec<_>>()
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_split_off_out_of_range() {
+        let mut list: DoublyLinkedList<i32> = (0..3).collect();
+        list.split_off(4);
+    }
+
+    #[test]
+    fn test_splice_append() {
+        for at in 0..=3 {
+            let mut list: DoublyLinkedList<i32> = (0..3).collect();
+            list.splice(at, (10..12).collect());
+            let mut oracle: Vec<i32> = (0..3).collect();
+            oracle.splice(at..at, 10..12);
+            assert_eq!(oracle, to_vec(&list));
+            assert_eq!(5, list.len());
+        }
+        let mut list: DoublyLinkedList<i32> = DoublyLinkedList::new();
+        let mut other: DoublyLinkedList<i32> = (1..=2).collect();
+        list.append(&mut other);
+        list.append(&mut DoublyLinkedList::new());
+        list.push_back(3);
+        assert!(other.is_empty());
+        assert_eq!("1 <-> 2 <-> 3", list.to_string());
+    }
+
+    // Weak back links, every element is dropped exactly once
+    #[test]
+    fn test_drop_elements() {
+        let tracker = Rc::new(());
+        let mut list: DoublyLinkedList<Rc<()>> = (0..5).map(|_| tracker.clone()).collect();
+        let rest = list.split_off(2);
+        list.pop_back();
+        assert_eq!(5, Rc::strong_count(&tracker));
+        drop(rest);
+        drop(list);
+        assert_eq!(1, Rc::strong_count(&tracker));
+    }
+
+    // dropping a long list does not overflow the stack
+    #[test]
+    fn test_long_list() {
+        let list: DoublyLinkedList<usize> = (0..1_000_000).collect();
+        assert_eq!(1_000_000, list.len());
+        assert_eq!(Some(999_999), list.into_iter().next_back());
+    }
+}
@@ -0,0 +1,246 @@
+// Iterators over a LinkedList
+// - iter() and iter_mut() borrow the elements from head to tail
+// - into_iter() moves the elements out, it can also be walked
+// from the back with rev() or next_back()
+// - for loops over list, &list and &mut list
+// - collect() into a LinkedList and extend() an existing one,
+// both keep the order of the iterator
+//
+// a singly linked list can not step backwards, so only the owning
+// IntoIter is double ended: it keeps the elements taken from the
+// back on a second list in reverse order
+
+use crate::LinkedList;
+use std::iter::FusedIterator;
+use std::mem;
+
+// borrowing iterator returned by LinkedList::iter
+pub struct Iter<'a, T> {
+    next: &'a LinkedList<T>,
+}
+
+// mutable borrowing iterator returned by LinkedList::iter_mut
+pub struct IterMut<'a, T> {
+    next: Option<&'a mut LinkedList<T>>,
+}
+
+// owning iterator returned by LinkedList::into_iter
+// front holds the remaining elements in order, back holds the
+// elements at the end of the list in reverse order
+pub struct IntoIter<T> {
+    front: LinkedList<T>,
+    back: LinkedList<T>,
+}
+
+impl<T> LinkedList<T> {
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { next: self }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut { next: Some(self) }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next {
+            LinkedList::Node(v, next) => {
+                self.next = next;
+                Some(v)
+            }
+            LinkedList::Nil => None,
+        }
+    }
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next.take()? {
+            LinkedList::Node(v, next) => {
+                self.next = Some(next);
+                Some(v)
+            }
+            LinkedList::Nil => None,
+        }
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front.is_empty() {
+            move_half(&mut self.back, &mut self.front);
+        }
+        self.front.pop_front()
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.back.is_empty() {
+            move_half(&mut self.front, &mut self.back);
+        }
+        self.back.pop_front()
+    }
+}
+
+// refill the empty list `to` from the far end of `from`
+// the far end is the tail of `from`, half of it (rounded up) is
+// split off and reversed onto `to`, so switching directions costs
+// O(n) only after O(n) elements were taken: amortized O(1) per element
+fn move_half<T>(from: &mut LinkedList<T>, to: &mut LinkedList<T>) {
+    let len = from.len();
+    let mut far = match from.link_at(len / 2) {
+        Some(link) => mem::replace(link, LinkedList::Nil),
+        None => return,
+    };
+    while let Some(v) = far.pop_front() {
+        to.push_front(v);
+    }
+}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+impl<T> FusedIterator for IterMut<'_, T> {}
+impl<T> FusedIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for LinkedList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            front: self,
+            back: LinkedList::Nil,
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a LinkedList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut LinkedList<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+// the first element of the iterator becomes the head
+impl<T> FromIterator<T> for LinkedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+// appends the elements at the end in the order of the iterator
+impl<T> Extend<T> for LinkedList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let mut end = self.end();
+        for v in iter {
+            end.push_front(v);
+            end = match end {
+                LinkedList::Node(_, next) => next,
+                LinkedList::Nil => unreachable!(),
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_iter() {
+        let list: LinkedList<i32> = (1..=4).collect();
+        assert_eq!("1 -> 2 -> 3 -> 4", list.to_string());
+        assert_eq!(vec![&1, &2, &3, &4], list.iter().collect::<Vec<_>>());
+        assert_eq!(6, list.iter().filter(|v| *v % 2 == 0).sum::<i32>());
+        let mut seen = vec![];
+        for v in &list {
+            seen.push(*v);
+        }
+        assert_eq!(vec![1, 2, 3, 4], seen);
+        let empty: LinkedList<i32> = LinkedList::new();
+        assert_eq!(None, empty.iter().next());
+    }
+
+    #[test]
+    fn test_iter_mut() {
+        let mut list: LinkedList<i32> = (1..=3).collect();
+        for v in list.iter_mut() {
+            *v *= 10;
+        }
+        for v in &mut list {
+            *v += 1;
+        }
+        assert_eq!("11 -> 21 -> 31", list.to_string());
+    }
+
+    #[test]
+    fn test_into_iter() {
+        let list: LinkedList<String> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+        let joined: Vec<String> = list.into_iter().collect();
+        assert_eq!(vec!["a", "b", "c"], joined);
+        let list: LinkedList<i32> = (1..=5).collect();
+        assert_eq!(
+            vec![5, 4, 3, 2, 1],
+            list.into_iter().rev().collect::<Vec<_>>()
+        );
+    }
+
+    // taking from both ends meets in the middle
+    #[test]
+    fn test_double_ended() {
+        for n in 0..20 {
+            let mut it = (0..n).collect::<LinkedList<_>>().into_iter();
+            let mut oracle = 0..n;
+            // alternate in an irregular pattern
+            for step in 0.. {
+                let (a, b) = if step % 3 == 0 {
+                    (it.next_back(), oracle.next_back())
+                } else {
+                    (it.next(), oracle.next())
+                };
+                assert_eq!(b, a);
+                if a.is_none() {
+                    break;
+                }
+            }
+            assert_eq!(None, it.next());
+            assert_eq!(None, it.next_back());
+        }
+    }
+
+    #[test]
+    fn test_extend() {
+        let mut list: LinkedList<i32> = LinkedList::new();
+        list.extend(vec![1, 2]);
+        list.extend(3..=4);
+        list.extend(Vec::new());
+        assert_eq!("1 -> 2 -> 3 -> 4", list.to_string());
+        let evens: LinkedList<i32> = list.iter().map(|v| v * 2).collect();
+        assert_eq!("2 -> 4 -> 6 -> 8", evens.to_string());
+    }
+
+    // collect and into_iter of a long list, the owning iterator
+    // drops what was not consumed
+    #[test]
+    fn test_long_iter() {
+        let list: LinkedList<usize> = (0..1_000_000).collect();
+        assert_eq!(1_000_000, list.iter().count());
+        let mut it = list.into_iter();
+        assert_eq!(Some(999_999), it.next_back());
+        assert_eq!(Some(0), it.next());
+    }
+}
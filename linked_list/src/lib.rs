@@ -0,0 +1,492 @@
+// In this code sample we are going to implement Linked List
+// with enum based representation
+// features to be implemented
+// - push and pop at the head of the list
+// - length of the list
+// - find an element by value or by a predicate
+// - display the list as 3 -> 2 -> 1
+// - elements can be of any type T, i.e. LinkedList<String>
+// or a list of structs searched with find_by
+// - len, find, Display, Debug, Clone and Drop walk the list in a loop instead of
+// recursing, so lists of millions of nodes do not overflow the stack
+// - in place editing through &mut self: push_front, pop_front, peek,
+// insert_at, remove_at, retain, append and clear
+// - iterators, for loops, collect and extend (see iter.rs)
+// - DoublyLinkedList with O(1) operations at both ends and a cursor
+// (see doubly.rs)
+// - PersistentList and ArcPersistentList, immutable lists where push and
+// tail share nodes with the old version (see persistent.rs)
+// - reverse, stable merge sort, merge, dedup and split_at without
+// allocating (see sort.rs)
+// - ArenaList, nodes in one Vec linked by u32 index, O(1) removal by
+// handle and reuse of freed slots (see arena.rs)
+
+use std::fmt::{Debug, Display, Formatter, Result};
+use std::mem::{self, ManuallyDrop};
+use std::ptr;
+
+pub mod arena;
+pub mod doubly;
+mod iter;
+pub mod persistent;
+mod sort;
+
+pub use arena::{ArenaList, Handle};
+pub use doubly::{CursorMut, DoublyLinkedList};
+pub use iter::{IntoIter, Iter, IterMut};
+pub use persistent::{ArcPersistentList, PersistentList};
+
+// define a enum which contains two variants
+// 1st variant contains data field and pointer to next element
+// 2nd variant contains Nil which indicates end of linked list
+// Debug and Clone are written by hand below, the derived ones
+// recurse once per node like Drop would
+pub enum LinkedList<T> {
+    Node(T, Box<LinkedList<T>>),
+    Nil,
+}
+
+// implementation of methods for LinkedList
+impl<T> LinkedList<T> {
+    // create a new empty linked list
+    pub fn new() -> Self {
+        LinkedList::Nil
+    }
+
+    // push node to the linked list
+    // Note: self parameter is not a reference so it takes the ownership
+    // same as push_front, kept for chaining list.push(1).push(2)
+    pub fn push(mut self, val: T) -> Self {
+        self.push_front(val);
+        self
+    }
+
+    // pop node from the head of the linked list
+    // Note: self parameter is not a reference so it takes the ownership
+    // it returns Option because the list could be empty
+    // same as pop_front, which does not need to move the list around
+    pub fn pop(mut self) -> (Self, Option<T>) {
+        let v = self.pop_front();
+        (self, v)
+    }
+
+    // add an element at the head of the list
+    pub fn push_front(&mut self, val: T) {
+        let rest = mem::replace(self, Self::Nil);
+        *self = Self::Node(val, Box::new(rest));
+    }
+
+    // remove the element at the head of the list
+    // returns None if the list is empty
+    pub fn pop_front(&mut self) -> Option<T> {
+        let (v, next) = mem::replace(self, Self::Nil).into_parts()?;
+        *self = *next;
+        Some(v)
+    }
+
+    // first element without removing it
+    pub fn peek(&self) -> Option<&T> {
+        match self {
+            Self::Node(v, _) => Some(v),
+            Self::Nil => None,
+        }
+    }
+
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
+        match self {
+            Self::Node(v, _) => Some(v),
+            Self::Nil => None,
+        }
+    }
+
+    // remove all elements
+    pub fn clear(&mut self) {
+        *self = Self::Nil;
+    }
+
+    // insert an element so that it ends up at position index
+    // index 0 is the head, index len() appends at the end
+    // Note: panics if index > len() just like Vec::insert
+    pub fn insert_at(&mut self, index: usize, val: T) {
+        self.link_at(index)
+            .unwrap_or_else(|| panic!("insertion index (is {index}) should be <= len"))
+            .push_front(val);
+    }
+
+    // remove the element at position index
+    // returns None if index is out of range
+    pub fn remove_at(&mut self, index: usize) -> Option<T> {
+        self.link_at(index)?.pop_front()
+    }
+
+    // keep only the elements for which the predicate returns true
+    // the order of the remaining elements does not change
+    pub fn retain<P: FnMut(&T) -> bool>(&mut self, mut predicate: P) {
+        let mut cur = self;
+        loop {
+            let keep = match cur {
+                Self::Node(v, _) => predicate(v),
+                Self::Nil => return,
+            };
+            if keep {
+                match cur {
+                    Self::Node(_, next) => cur = next,
+                    Self::Nil => return,
+                }
+            } else {
+                cur.pop_front();
+            }
+        }
+    }
+
+    // move all elements of other to the end of this list
+    // other is left empty
+    pub fn append(&mut self, other: &mut Self) {
+        *self.end() = mem::replace(other, Self::Nil);
+    }
+
+    // calculate length of the linked list
+    pub fn len(&self) -> usize {
+        let mut len = 0;
+        let mut cur = self;
+        while let Self::Node(_, next) = cur {
+            len += 1;
+            cur = next;
+        }
+        len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        matches!(self, Self::Nil)
+    }
+
+    // find the first element for which the predicate returns true
+    // i.e. list.find_by(|p| p.name == "Alice")
+    pub fn find_by<P: Fn(&T) -> bool>(&self, predicate: P) -> Option<&T> {
+        let mut cur = self;
+        while let Self::Node(v, next) = cur {
+            if predicate(v) {
+                return Some(v);
+            }
+            cur = next;
+        }
+        None
+    }
+
+    // the link (either a node or the final Nil) at position index,
+    // None if the list has fewer than index elements
+    fn link_at(&mut self, index: usize) -> Option<&mut Self> {
+        let mut cur = self;
+        for _ in 0..index {
+            match cur {
+                Self::Node(_, next) => cur = next,
+                Self::Nil => return None,
+            }
+        }
+        Some(cur)
+    }
+
+    // the Nil at the end of the list
+    fn end(&mut self) -> &mut Self {
+        let mut cur = self;
+        while let Self::Node(_, next) = cur {
+            cur = next;
+        }
+        cur
+    }
+
+    // move the value and the rest of the list out of a node
+    // a type with Drop cannot be destructured by value, so the
+    // fields are read out of a ManuallyDrop instead
+    fn into_parts(self) -> Option<(T, Box<Self>)> {
+        let this = ManuallyDrop::new(self);
+        match &*this {
+            Self::Nil => None,
+            // Safety: `this` is never used or dropped again,
+            // so each field is moved out exactly once
+            Self::Node(v, next) => unsafe { Some((ptr::read(v), ptr::read(next))) },
+        }
+    }
+}
+
+impl<T: PartialEq> LinkedList<T> {
+    // find an element in the linked list returns Option<&T>
+    pub fn find(&self, val: &T) -> Option<&T> {
+        self.find_by(|v| v == val)
+    }
+}
+
+impl<T> Default for LinkedList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// the default drop would drop the Box of the next node from inside
+// the drop of this node, recursing once per node
+// instead unlink the nodes one at a time, each node is dropped
+// with a Nil tail so its own drop has nothing left to do
+impl<T> Drop for LinkedList<T> {
+    fn drop(&mut self) {
+        if let Self::Node(_, next) = self {
+            let mut cur = mem::replace(&mut **next, Self::Nil);
+            while let Self::Node(_, next) = &mut cur {
+                let rest = mem::replace(&mut **next, Self::Nil);
+                cur = rest;
+            }
+        }
+    }
+}
+
+// copies the elements in a loop, see iter.rs for collect
+impl<T: Clone> Clone for LinkedList<T> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+// prints like a Vec, [3, 2, 1]
+impl<T: Debug> Debug for LinkedList<T> {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: Display> Display for LinkedList<T> {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let mut cur = self;
+        while let Self::Node(v, next) = cur {
+            write!(f, "{}", v)?;
+            if let Self::Node(..) = **next {
+                write!(f, " -> ")?;
+            }
+            cur = next;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // Debug prints the elements like a Vec, Clone copies them
+    #[test]
+    fn test_debug_clone() {
+        let list = LinkedList::new().push(1).push(2);
+        assert_eq!("[2, 1]", format!("{list:?}"));
+        assert_eq!("[]", format!("{:?}", LinkedList::<i32>::new()));
+        let mut copy = list.clone();
+        copy.push_front(3);
+        assert_eq!("3 -> 2 -> 1", copy.to_string());
+        assert_eq!("2 -> 1", list.to_string());
+    }
+
+    #[test]
+    fn test_blank_list() {
+        let list: LinkedList<i32> = LinkedList::new();
+        assert_eq!(0, list.len());
+        assert!(list.is_empty());
+        assert_eq!("".to_string(), list.to_string());
+        assert_eq!(None, list.find(&1));
+    }
+
+    #[test]
+    fn test_list() {
+        let list = LinkedList::new();
+        let list = list.push(1);
+        let list = list.push(2);
+        let list = list.push(3);
+        assert_eq!(3, list.len());
+        assert_eq!("3 -> 2 -> 1".to_string(), list.to_string());
+        assert_eq!(Some(&3), list.find(&3));
+        assert_eq!(None, list.find(&4));
+
+        let (list, elem) = list.pop();
+        assert_eq!(2, list.len());
+        assert_eq!(Some(3), elem);
+
+        let (list, elem) = list.pop();
+        assert_eq!(1, list.len());
+        assert_eq!(Some(2), elem);
+
+        let (list, elem) = list.pop();
+        assert_eq!(0, list.len());
+        assert_eq!(Some(1), elem);
+
+        let (list, elem) = list.pop();
+        assert_eq!(0, list.len());
+        assert_eq!(None, elem);
+    }
+
+    // any element type which can be displayed
+    #[test]
+    fn test_string_list() {
+        let list = LinkedList::new()
+            .push("world".to_string())
+            .push("hello".to_string());
+        assert_eq!("hello -> world", list.to_string());
+        assert_eq!(Some(&"world".to_string()), list.find(&"world".to_string()));
+    }
+
+    // structs without PartialEq or Display are searched with find_by
+    #[test]
+    fn test_find_by() {
+        #[derive(Debug)]
+        struct Person {
+            name: &'static str,
+            age: u32,
+        }
+        let list = LinkedList::new()
+            .push(Person {
+                name: "Alice",
+                age: 30,
+            })
+            .push(Person {
+                name: "Bob",
+                age: 25,
+            })
+            .push(Person {
+                name: "Carol",
+                age: 35,
+            });
+        let bob = list.find_by(|p| p.name == "Bob").unwrap();
+        assert_eq!(25, bob.age);
+        let older = list.find_by(|p| p.age > 30).unwrap();
+        assert_eq!("Carol", older.name);
+        assert!(list.find_by(|p| p.age > 40).is_none());
+    }
+
+    // none of the operations recurse, a list this long would
+    // overflow the stack of the test thread many times over
+    #[test]
+    fn test_long_list() {
+        let n = 10_000_000;
+        let mut list = LinkedList::new();
+        for i in 0..n {
+            list = list.push(i);
+        }
+        assert_eq!(n, list.len());
+        assert_eq!(Some(&0), list.find(&0));
+        assert_eq!(None, list.find(&n));
+        let (list, elem) = list.pop();
+        assert_eq!(Some(n - 1), elem);
+        assert_eq!(n - 1, list.len());
+        let copy = list.clone();
+        assert_eq!(n - 1, copy.len());
+        assert_eq!(copy.peek(), list.peek());
+        drop(copy);
+        let debug = format!("{list:?}");
+        assert!(debug.starts_with("[9999998, 9999997, "));
+        assert!(debug.ends_with(", 1, 0]"));
+        drop(list);
+    }
+
+    // Display of a long list, checked by its length
+    #[test]
+    fn test_long_display() {
+        let mut list = LinkedList::new();
+        for _ in 0..1_000_000 {
+            list = list.push(7);
+        }
+        let s = list.to_string();
+        assert_eq!(1_000_000 + 4 * 999_999, s.len());
+        assert!(s.starts_with("7 -> 7") && s.ends_with("7 -> 7"));
+    }
+
+    // every element is dropped exactly once
+    #[test]
+    fn test_drop_elements() {
+        use std::rc::Rc;
+        let counter = Rc::new(());
+        let mut list = LinkedList::new();
+        for _ in 0..1000 {
+            list = list.push(Rc::clone(&counter));
+        }
+        let (list, elem) = list.pop();
+        assert_eq!(1001, Rc::strong_count(&counter));
+        drop(elem);
+        assert_eq!(1000, Rc::strong_count(&counter));
+        drop(list);
+        assert_eq!(1, Rc::strong_count(&counter));
+    }
+
+    // the &mut API edits the list in place
+    #[test]
+    fn test_push_pop_front() {
+        let mut list = LinkedList::new();
+        list.push_front(1);
+        list.push_front(2);
+        assert_eq!(Some(&2), list.peek());
+        if let Some(v) = list.peek_mut() {
+            *v = 20;
+        }
+        assert_eq!("20 -> 1", list.to_string());
+        assert_eq!(Some(20), list.pop_front());
+        assert_eq!(Some(1), list.pop_front());
+        assert_eq!(None, list.pop_front());
+        assert_eq!(None, list.peek());
+        assert_eq!(None, list.peek_mut());
+    }
+
+    // same result as the by-value wrappers
+    #[test]
+    fn test_wrappers() {
+        let mut a = LinkedList::new();
+        a.push_front(1);
+        a.push_front(2);
+        let b = LinkedList::new().push(1).push(2);
+        assert_eq!(a.to_string(), b.to_string());
+        let (b, elem) = b.pop();
+        assert_eq!(a.pop_front(), elem);
+        assert_eq!(a.to_string(), b.to_string());
+    }
+
+    #[test]
+    fn test_insert_remove_at() {
+        let mut list = LinkedList::new().push(3).push(1);
+        list.insert_at(1, 2);
+        list.insert_at(3, 4);
+        list.insert_at(0, 0);
+        assert_eq!("0 -> 1 -> 2 -> 3 -> 4", list.to_string());
+        assert_eq!(Some(4), list.remove_at(4));
+        assert_eq!(Some(0), list.remove_at(0));
+        assert_eq!(Some(2), list.remove_at(1));
+        assert_eq!(None, list.remove_at(2));
+        assert_eq!("1 -> 3", list.to_string());
+    }
+
+    // Note: panics like Vec::insert
+    #[test]
+    #[should_panic(expected = "insertion index (is 3) should be <= len")]
+    fn test_insert_out_of_range() {
+        let mut list = LinkedList::new().push(1);
+        list.insert_at(3, 2);
+    }
+
+    #[test]
+    fn test_retain() {
+        let mut list = LinkedList::new();
+        for i in (0..10).rev() {
+            list.push_front(i);
+        }
+        list.retain(|v| v % 3 != 0);
+        assert_eq!("1 -> 2 -> 4 -> 5 -> 7 -> 8", list.to_string());
+        list.retain(|_| false);
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn test_append_clear() {
+        let mut a = LinkedList::new().push(2).push(1);
+        let mut b = LinkedList::new().push(4).push(3);
+        a.append(&mut b);
+        assert_eq!("1 -> 2 -> 3 -> 4", a.to_string());
+        assert!(b.is_empty());
+        b.append(&mut a);
+        assert_eq!(4, b.len());
+        assert!(a.is_empty());
+        b.clear();
+        assert!(b.is_empty());
+    }
+}
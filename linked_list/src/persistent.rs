@@ -0,0 +1,328 @@
+// Persistent linked list
+// push on LinkedList moves the list into the new one, the old version
+// is gone. Here a list is never changed after it is built: push makes
+// a new head node that points at the existing list, and tail hands out
+// the list after the head, both in O(1). Every version stays usable
+// and versions share their common nodes, so keeping a whole history
+// (e.g. an undo stack) costs one node per push
+// - PersistentList shares nodes with Rc, for one thread
+// - ArcPersistentList shares nodes with Arc and can be sent to and
+// read from other threads
+// - clone is O(1), it only copies a pointer
+// - head, len and is_empty in O(1), iter, find_by and display as 3 -> 2 -> 1
+// - drop walks the nodes no other version uses in a loop, so dropping
+// long lists does not overflow the stack
+//
+// both types are written once in the macro below, they only differ in
+// the pointer type
+
+use std::fmt::{self, Debug, Display, Formatter};
+use std::iter::FusedIterator;
+use std::rc::Rc;
+use std::sync::Arc;
+
+macro_rules! persistent_list {
+    ($list:ident, $node:ident, $iter:ident, $ptr:ident) => {
+        pub struct $list<T> {
+            head: Option<$ptr<$node<T>>>,
+            len: usize,
+        }
+
+        struct $node<T> {
+            val: T,
+            next: Option<$ptr<$node<T>>>,
+        }
+
+        impl<T> $list<T> {
+            // create a new empty list
+            pub fn new() -> Self {
+                Self { head: None, len: 0 }
+            }
+
+            // a new version with val in front, self is left as it was
+            pub fn push(&self, val: T) -> Self {
+                let node = $node {
+                    val,
+                    next: self.head.clone(),
+                };
+                Self {
+                    head: Some($ptr::new(node)),
+                    len: self.len + 1,
+                }
+            }
+
+            // the list without its first element, shares all of its nodes
+            // the tail of an empty list is empty
+            pub fn tail(&self) -> Self {
+                match &self.head {
+                    Some(node) => Self {
+                        head: node.next.clone(),
+                        len: self.len - 1,
+                    },
+                    None => Self::new(),
+                }
+            }
+
+            // first element
+            pub fn head(&self) -> Option<&T> {
+                self.head.as_ref().map(|node| &node.val)
+            }
+
+            pub fn len(&self) -> usize {
+                self.len
+            }
+
+            pub fn is_empty(&self) -> bool {
+                self.head.is_none()
+            }
+
+            // true if both lists are the same version, i.e. share every node
+            pub fn ptr_eq(&self, other: &Self) -> bool {
+                match (&self.head, &other.head) {
+                    (Some(a), Some(b)) => $ptr::ptr_eq(a, b),
+                    (None, None) => true,
+                    _ => false,
+                }
+            }
+
+            pub fn iter(&self) -> $iter<'_, T> {
+                $iter {
+                    next: self.head.as_deref(),
+                    len: self.len,
+                }
+            }
+
+            // search for an element by a predicate
+            pub fn find_by<P: Fn(&T) -> bool>(&self, predicate: P) -> Option<&T> {
+                self.iter().find(|v| predicate(v))
+            }
+        }
+
+        // iterator over the elements from head to tail
+        pub struct $iter<'a, T> {
+            next: Option<&'a $node<T>>,
+            len: usize,
+        }
+
+        impl<'a, T> Iterator for $iter<'a, T> {
+            type Item = &'a T;
+            fn next(&mut self) -> Option<Self::Item> {
+                let node = self.next?;
+                self.next = node.next.as_deref();
+                self.len -= 1;
+                Some(&node.val)
+            }
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.len, Some(self.len))
+            }
+        }
+
+        impl<T> ExactSizeIterator for $iter<'_, T> {}
+        impl<T> FusedIterator for $iter<'_, T> {}
+
+        impl<'a, T> IntoIterator for &'a $list<T> {
+            type Item = &'a T;
+            type IntoIter = $iter<'a, T>;
+            fn into_iter(self) -> Self::IntoIter {
+                self.iter()
+            }
+        }
+
+        // a new pointer to the same nodes, T does not need to be Clone
+        impl<T> Clone for $list<T> {
+            fn clone(&self) -> Self {
+                Self {
+                    head: self.head.clone(),
+                    len: self.len,
+                }
+            }
+        }
+
+        impl<T> Default for $list<T> {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        // the first element of the iterator becomes the head
+        // Note: the elements are collected first, the list is built
+        // from the back
+        impl<T> FromIterator<T> for $list<T> {
+            fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+                let items: Vec<T> = iter.into_iter().collect();
+                items
+                    .into_iter()
+                    .rev()
+                    .fold(Self::new(), |list, v| list.push(v))
+            }
+        }
+
+        // a node is freed only by the last version that points at it,
+        // stop at the first node that is still shared
+        // into_inner gives the node to exactly one of the owners letting
+        // go at the same time, with try_unwrap two threads could both
+        // fail and the last pointer would drop the rest recursively
+        impl<T> Drop for $list<T> {
+            fn drop(&mut self) {
+                let mut cur = self.head.take();
+                while let Some(node) = cur {
+                    cur = $ptr::into_inner(node).and_then(|mut node| node.next.take());
+                }
+            }
+        }
+
+        impl<T: PartialEq> PartialEq for $list<T> {
+            fn eq(&self, other: &Self) -> bool {
+                self.len == other.len && self.iter().eq(other.iter())
+            }
+        }
+
+        impl<T: Display> Display for $list<T> {
+            fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+                for (i, v) in self.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " -> ")?;
+                    }
+                    write!(f, "{v}")?;
+                }
+                Ok(())
+            }
+        }
+
+        // prints like a Vec, [3, 2, 1]
+        impl<T: Debug> Debug for $list<T> {
+            fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+                f.debug_list().entries(self.iter()).finish()
+            }
+        }
+    };
+}
+
+persistent_list!(PersistentList, Node, Iter, Rc);
+persistent_list!(ArcPersistentList, ArcNode, ArcIter, Arc);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_blank_list() {
+        let list: PersistentList<i32> = PersistentList::new();
+        assert!(list.is_empty());
+        assert_eq!(None, list.head());
+        assert!(list.tail().is_empty());
+        assert_eq!("", list.to_string());
+        assert_eq!("[]", format!("{list:?}"));
+    }
+
+    #[test]
+    fn test_push_tail() {
+        let list = PersistentList::new().push(1).push(2).push(3);
+        assert_eq!(Some(&3), list.head());
+        assert_eq!(3, list.len());
+        assert_eq!("3 -> 2 -> 1", list.to_string());
+        let tail = list.tail();
+        assert_eq!("2 -> 1", tail.to_string());
+        assert_eq!(Some(&2), tail.find_by(|v| v % 2 == 0));
+        assert_eq!(vec![&3, &2, &1], list.iter().collect::<Vec<_>>());
+        assert_eq!(list, [3, 2, 1].into_iter().collect());
+    }
+
+    // older versions stay as they were and share their nodes
+    #[test]
+    fn test_versions() {
+        let base = PersistentList::new().push("a").push("b");
+        let left = base.push("c");
+        let right = base.push("d").push("e");
+        assert_eq!("b -> a", base.to_string());
+        assert_eq!("c -> b -> a", left.to_string());
+        assert_eq!("e -> d -> b -> a", right.to_string());
+        assert!(left.tail().ptr_eq(&base));
+        assert!(right.tail().tail().ptr_eq(&base));
+        assert!(!left.ptr_eq(&right));
+        // base, left and right all point at the "b" node
+        assert_eq!(3, Rc::strong_count(base.head.as_ref().unwrap()));
+        drop(left);
+        assert_eq!(2, Rc::strong_count(base.head.as_ref().unwrap()));
+    }
+
+    // an undo history: every state is kept and going back is O(1)
+    #[test]
+    fn test_undo_stack() {
+        let mut history = vec![PersistentList::new()];
+        for word in ["hello", "big", "world"] {
+            let next = history.last().unwrap().push(word.to_string());
+            history.push(next);
+        }
+        let undone = history[3].tail();
+        assert_eq!(history[2], undone);
+        assert!(history[2].ptr_eq(&undone));
+        assert_eq!("world -> big -> hello", history[3].to_string());
+        assert_eq!("hello", history[1].to_string());
+    }
+
+    // elements are dropped once, when the last version using them goes
+    #[test]
+    fn test_drop_elements() {
+        let tracker = Rc::new(());
+        let base: PersistentList<Rc<()>> = (0..3).map(|_| tracker.clone()).collect();
+        let longer = base.push(tracker.clone());
+        assert_eq!(5, Rc::strong_count(&tracker));
+        drop(base);
+        assert_eq!(5, Rc::strong_count(&tracker));
+        let tail = longer.tail().tail();
+        drop(longer);
+        assert_eq!(3, Rc::strong_count(&tracker));
+        drop(tail);
+        assert_eq!(1, Rc::strong_count(&tracker));
+    }
+
+    // dropping a long list does not overflow the stack
+    #[test]
+    fn test_long_list() {
+        let list: PersistentList<usize> = (0..1_000_000).collect();
+        let shared = list.tail().tail();
+        assert_eq!(999_998, shared.len());
+        drop(list);
+        assert_eq!(Some(&2), shared.head());
+    }
+
+    // versions sharing a long tail dropped on several threads at once
+    // free it without recursing, whichever thread lets go last
+    #[test]
+    fn test_arc_drop_threads() {
+        for _ in 0..4 {
+            let base: ArcPersistentList<usize> = (0..200_000).collect();
+            let handles: Vec<_> = (0..4)
+                .map(|i| {
+                    let list = base.push(i);
+                    thread::spawn(move || drop(list))
+                })
+                .collect();
+            drop(base);
+            for handle in handles {
+                handle.join().unwrap();
+            }
+        }
+    }
+
+    // versions of the Arc list can be read from other threads
+    #[test]
+    fn test_arc_list() {
+        let base: ArcPersistentList<i32> = (1..=3).collect();
+        let handles: Vec<_> = (0..4)
+            .map(|i| {
+                let list = base.push(i * 10);
+                thread::spawn(move || (list.iter().sum::<i32>(), list.tail()))
+            })
+            .collect();
+        for (i, handle) in handles.into_iter().enumerate() {
+            let (sum, tail) = handle.join().unwrap();
+            assert_eq!(i as i32 * 10 + 6, sum);
+            assert!(tail.ptr_eq(&base));
+        }
+        assert_eq!("1 -> 2 -> 3", base.to_string());
+        assert_eq!(1, Arc::strong_count(base.head.as_ref().unwrap()));
+    }
+}
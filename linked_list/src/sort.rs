@@ -0,0 +1,297 @@
+// Ordering operations on LinkedList
+// - reverse in place
+// - sort, sort_by and sort_by_key: a stable merge sort in O(n log n)
+// - merge two sorted lists into one sorted list
+// - dedup removes consecutive repeated elements
+// - split_at cuts a list in two at an index
+//
+// none of them allocate: nodes are moved from one list to another by
+// swapping links, so every Box is reused where it is
+
+use crate::LinkedList;
+use std::cmp::Ordering;
+use std::mem;
+
+impl<T> LinkedList<T> {
+    // reverse the order of the elements
+    pub fn reverse(&mut self) {
+        let mut reversed = Self::Nil;
+        while !self.is_empty() {
+            self.move_head_to(&mut reversed);
+        }
+        *self = reversed;
+    }
+
+    // sort with a comparison function
+    // stable: equal elements keep their order
+    pub fn sort_by<F: FnMut(&T, &T) -> Ordering>(&mut self, mut compare: F) {
+        let len = self.len();
+        merge_sort(self, len, &mut compare);
+    }
+
+    // sort by a key extracted from each element, the key function is
+    // called for every comparison
+    pub fn sort_by_key<K: Ord, F: FnMut(&T) -> K>(&mut self, mut f: F) {
+        self.sort_by(|a, b| f(a).cmp(&f(b)));
+    }
+
+    // split the list in two, the first list holds [0, at) and the
+    // second list [at, len)
+    // Note: panics if at > len() just like slice::split_at
+    pub fn split_at(self, at: usize) -> (Self, Self) {
+        let mut front = self;
+        let back = front
+            .link_at(at)
+            .unwrap_or_else(|| panic!("split index (is {at}) should be <= len"));
+        let back = mem::replace(back, Self::Nil);
+        (front, back)
+    }
+
+    // move the head node of self to the front of dst, self must not be
+    // empty. Only the links are swapped, the node itself is not moved
+    //   self = Node(v, rest), dst = old  ->  self = rest, dst = Node(v, old)
+    fn move_head_to(&mut self, dst: &mut Self) {
+        if let Self::Node(_, next) = self {
+            mem::swap(&mut **next, dst);
+            mem::swap(self, dst);
+        }
+    }
+}
+
+impl<T: Ord> LinkedList<T> {
+    // sort in ascending order, stable
+    pub fn sort(&mut self) {
+        self.sort_by(T::cmp);
+    }
+
+    // merge a sorted list into this sorted list, the result is sorted
+    // and other is left empty. Equal elements of self come first
+    pub fn merge(&mut self, other: &mut Self) {
+        let a = mem::replace(self, Self::Nil);
+        let b = mem::replace(other, Self::Nil);
+        *self = merge_by(a, b, &mut T::cmp);
+    }
+}
+
+impl<T: PartialEq> LinkedList<T> {
+    // remove consecutive repeated elements, like Vec::dedup
+    // on a sorted list this removes every duplicate
+    pub fn dedup(&mut self) {
+        let mut cur = self;
+        while let Self::Node(v, next) = cur {
+            while matches!(&**next, LinkedList::Node(w, _) if w == v) {
+                next.pop_front();
+            }
+            cur = next;
+        }
+    }
+}
+
+// sort the first len elements of list, which has exactly len elements
+// top down: split in the middle, sort both halves and merge them
+// the recursion is only log2(len) deep
+fn merge_sort<T, F: FnMut(&T, &T) -> Ordering>(
+    list: &mut LinkedList<T>,
+    len: usize,
+    compare: &mut F,
+) {
+    if len < 2 {
+        return;
+    }
+    let half = len / 2;
+    let mut back = match list.link_at(half) {
+        Some(link) => mem::replace(link, LinkedList::Nil),
+        None => return,
+    };
+    merge_sort(list, half, compare);
+    merge_sort(&mut back, len - half, compare);
+    let front = mem::replace(list, LinkedList::Nil);
+    *list = merge_by(front, back, compare);
+}
+
+// merge two sorted lists, on equal elements a goes first
+// the result is built at its end, end always points at its last link
+fn merge_by<T, F: FnMut(&T, &T) -> Ordering>(
+    mut a: LinkedList<T>,
+    mut b: LinkedList<T>,
+    compare: &mut F,
+) -> LinkedList<T> {
+    let mut merged = LinkedList::Nil;
+    let mut end = &mut merged;
+    loop {
+        let from = match (a.peek(), b.peek()) {
+            (Some(x), Some(y)) if compare(y, x) == Ordering::Less => &mut b,
+            (Some(_), Some(_)) => &mut a,
+            _ => break,
+        };
+        from.move_head_to(end);
+        end = match end {
+            LinkedList::Node(_, next) => next,
+            LinkedList::Nil => unreachable!(),
+        };
+    }
+    // one list is empty, the rest of the other one is already sorted
+    *end = if a.is_empty() { b } else { a };
+    merged
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // pseudo random numbers, the same every run
+    fn random_vec(len: usize, max: u64, seed: u64) -> Vec<u64> {
+        let mut x = seed;
+        (0..len)
+            .map(|_| {
+                x = x
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                (x >> 33) % max
+            })
+            .collect()
+    }
+
+    fn to_vec<T: Clone>(list: &LinkedList<T>) -> Vec<T> {
+        list.iter().cloned().collect()
+    }
+
+    #[test]
+    fn test_reverse() {
+        for n in 0..6 {
+            let mut list: LinkedList<usize> = (0..n).collect();
+            list.reverse();
+            assert_eq!((0..n).rev().collect::<Vec<_>>(), to_vec(&list));
+        }
+        let mut long: LinkedList<usize> = (0..1_000_000).collect();
+        long.reverse();
+        assert_eq!(Some(&999_999), long.peek());
+    }
+
+    // lengths around powers of two, many repeated values
+    #[test]
+    fn test_sort() {
+        for (len, seed) in [(0, 1), (1, 2), (2, 3), (7, 4), (8, 5), (33, 6), (1000, 7)] {
+            let mut oracle = random_vec(len, 20, seed);
+            let mut list: LinkedList<u64> = oracle.iter().copied().collect();
+            oracle.sort();
+            list.sort();
+            assert_eq!(oracle, to_vec(&list));
+        }
+    }
+
+    #[test]
+    fn test_sort_by() {
+        let mut oracle = random_vec(500, 1000, 42);
+        let mut list: LinkedList<u64> = oracle.iter().copied().collect();
+        oracle.sort_by(|a, b| b.cmp(a));
+        list.sort_by(|a, b| b.cmp(a));
+        assert_eq!(oracle, to_vec(&list));
+    }
+
+    // equal keys keep their order, the index shows the original position
+    #[test]
+    fn test_sort_stable() {
+        let mut oracle: Vec<(u64, usize)> = random_vec(300, 5, 9).into_iter().zip(0..).collect();
+        let mut list: LinkedList<(u64, usize)> = oracle.iter().copied().collect();
+        oracle.sort_by_key(|p| p.0);
+        list.sort_by_key(|p| p.0);
+        assert_eq!(oracle, to_vec(&list));
+        let mut words: LinkedList<&str> = ["pear", "fig", "apple", "kiwi", "plum"]
+            .into_iter()
+            .collect();
+        words.sort_by_key(|w| w.len());
+        assert_eq!("fig -> pear -> kiwi -> plum -> apple", words.to_string());
+    }
+
+    #[test]
+    fn test_sort_long() {
+        let mut oracle = random_vec(200_000, u64::MAX, 11);
+        let mut list: LinkedList<u64> = oracle.iter().copied().collect();
+        oracle.sort();
+        list.sort();
+        assert_eq!(oracle, to_vec(&list));
+    }
+
+    #[test]
+    fn test_merge() {
+        for seed in 0..20 {
+            let mut a = random_vec(seed as usize % 7, 10, seed);
+            let mut b = random_vec(seed as usize % 5, 10, seed + 100);
+            a.sort();
+            b.sort();
+            let mut list: LinkedList<u64> = a.iter().copied().collect();
+            let mut other: LinkedList<u64> = b.iter().copied().collect();
+            list.merge(&mut other);
+            let mut oracle = [a, b].concat();
+            oracle.sort();
+            assert_eq!(oracle, to_vec(&list));
+            assert!(other.is_empty());
+        }
+    }
+
+    // compared by the number only, the name shows where it came from
+    #[derive(Debug, Clone)]
+    struct Tagged(u8, &'static str);
+
+    impl PartialEq for Tagged {
+        fn eq(&self, other: &Self) -> bool {
+            self.0 == other.0
+        }
+    }
+    impl Eq for Tagged {}
+    impl PartialOrd for Tagged {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+    impl Ord for Tagged {
+        fn cmp(&self, other: &Self) -> Ordering {
+            self.0.cmp(&other.0)
+        }
+    }
+
+    // on equal elements the ones from self come first
+    #[test]
+    fn test_merge_stable() {
+        let mut list: LinkedList<Tagged> = [Tagged(1, "a"), Tagged(2, "a")].into_iter().collect();
+        let mut other: LinkedList<Tagged> = [Tagged(1, "b"), Tagged(2, "b")].into_iter().collect();
+        list.merge(&mut other);
+        let tags: Vec<_> = list.iter().map(|t| (t.0, t.1)).collect();
+        assert_eq!(vec![(1, "a"), (1, "b"), (2, "a"), (2, "b")], tags);
+    }
+
+    #[test]
+    fn test_dedup() {
+        for seed in 0..10 {
+            let mut oracle = random_vec(50, 3, seed);
+            let mut list: LinkedList<u64> = oracle.iter().copied().collect();
+            oracle.dedup();
+            list.dedup();
+            assert_eq!(oracle, to_vec(&list));
+        }
+        let mut list: LinkedList<u64> = random_vec(100, 10, 3).into_iter().collect();
+        list.sort();
+        list.dedup();
+        assert_eq!((0..10).collect::<Vec<_>>(), to_vec(&list));
+    }
+
+    #[test]
+    fn test_split_at() {
+        for at in 0..=5 {
+            let list: LinkedList<usize> = (0..5).collect();
+            let (front, back) = list.split_at(at);
+            let oracle: Vec<usize> = (0..5).collect();
+            let (a, b) = oracle.split_at(at);
+            assert_eq!(a, to_vec(&front));
+            assert_eq!(b, to_vec(&back));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_split_at_out_of_range() {
+        let list: LinkedList<i32> = (0..3).collect();
+        let _ = list.split_at(4);
+    }
+}